@@ -0,0 +1,113 @@
+use crate::Result::{self, Err, Ok};
+
+pub fn take<F>(input: &[u8], count: usize) -> Result<&[u8], &[u8], F> {
+    if input.len() < count {
+        Err
+    } else {
+        let (taken, rest) = input.split_at(count);
+        Ok(taken, rest)
+    }
+}
+
+pub fn exact<'a, F>(input: &'a [u8], expected: &[u8]) -> Result<&'a [u8], &'a [u8], F> {
+    take(input, expected.len()).and(|taken, rest| {
+        if taken == expected {
+            Ok(taken, rest)
+        } else {
+            Err
+        }
+    })
+}
+
+pub fn length_prefixed<'a, L: TryInto<usize>, F>(
+    input: &'a [u8],
+    length: impl FnOnce(&'a [u8]) -> Result<L, &'a [u8], F>,
+) -> Result<&'a [u8], &'a [u8], F> {
+    length(input).and(|length, rest| match length.try_into() {
+        core::result::Result::Ok(length) => take(rest, length),
+        core::result::Result::Err(_) => Err,
+    })
+}
+
+macro_rules! integers {
+    ($($be:ident $le:ident $type:ty),* $(,)?) => {
+        $(
+            pub fn $be<F>(input: &[u8]) -> Result<$type, &[u8], F> {
+                take(input, core::mem::size_of::<$type>())
+                    .map(|taken| <$type>::from_be_bytes(taken.try_into().unwrap()))
+            }
+
+            pub fn $le<F>(input: &[u8]) -> Result<$type, &[u8], F> {
+                take(input, core::mem::size_of::<$type>())
+                    .map(|taken| <$type>::from_le_bytes(taken.try_into().unwrap()))
+            }
+        )*
+    };
+}
+
+integers! {
+    be_u16 le_u16 u16,
+    be_u32 le_u32 u32,
+    be_u64 le_u64 u64,
+    be_u128 le_u128 u128,
+    be_i16 le_i16 i16,
+    be_i32 le_i32 i32,
+    be_i64 le_i64 i64,
+    be_i128 le_i128 i128,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::one_part;
+
+    #[test]
+    fn test_taking_one_byte() {
+        assert_eq!(one_part::<_, ()>(&b"ab"[..]), Ok(b'a', &b"b"[..]));
+
+        assert_eq!(one_part::<_, ()>(&b""[..]), Err);
+    }
+
+    #[test]
+    fn test_taking_bytes() {
+        assert_eq!(take::<()>(b"abc", 2), Ok(&b"ab"[..], &b"c"[..]));
+
+        assert_eq!(take::<()>(b"abc", 3), Ok(&b"abc"[..], &b""[..]));
+
+        assert_eq!(take::<()>(b"abc", 4), Err);
+    }
+
+    #[test]
+    fn test_matching_exact_bytes() {
+        assert_eq!(exact::<()>(b"GET /", b"GET"), Ok(&b"GET"[..], &b" /"[..]));
+
+        assert_eq!(exact::<()>(b"PUT /", b"GET"), Err);
+
+        assert_eq!(exact::<()>(b"GE", b"GET"), Err);
+    }
+
+    #[test]
+    fn test_integers() {
+        let input = &[0x12, 0x34, 0x56][..];
+
+        assert_eq!(be_u16::<()>(input), Ok(0x1234, &[0x56][..]));
+
+        assert_eq!(le_u16::<()>(input), Ok(0x3412, &[0x56][..]));
+
+        assert_eq!(be_i32::<()>(&[0xff, 0xff, 0xff, 0xfe]), Ok(-2, &[][..]));
+
+        assert_eq!(le_u32::<()>(input), Err);
+    }
+
+    #[test]
+    fn test_length_prefixed() {
+        assert_eq!(
+            length_prefixed::<_, ()>(b"\x00\x03abcd", be_u16),
+            Ok(&b"abc"[..], &b"d"[..])
+        );
+
+        assert_eq!(length_prefixed::<_, ()>(b"\x05abc", one_part), Err);
+
+        assert_eq!(length_prefixed::<_, ()>(b"", one_part), Err);
+    }
+}
@@ -0,0 +1,432 @@
+use crate::{visual_width, Advance, Newlines, Position, Span};
+use core::fmt::Write;
+use core::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Style {
+    /// No escape sequences, good for tests and log files
+    #[default]
+    Plain,
+    /// Colored with ANSI escape sequences
+    Ansi,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// A rustc-style report about a place (or several places) in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub severity: Severity,
+    pub message: String,
+    /// The first label is the primary one, it is underlined with `^` and shown in the header
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    /// How the source is split into lines, should be the same as in the input the positions of
+    /// the labels come from
+    pub newlines: Newlines,
+}
+
+impl Report {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            newlines: Newlines::default(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn label(mut self, span: impl Into<Span>, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span: span.into(),
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_newlines(self, newlines: Newlines) -> Self {
+        Self { newlines, ..self }
+    }
+
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn render(&self, source: &str, style: Style) -> String {
+        let paint = Paint(style);
+        let mut output = String::new();
+
+        let (severity, color) = match self.severity {
+            Severity::Error => ("error", RED),
+            Severity::Warning => ("warning", YELLOW),
+            Severity::Note => ("note", GREEN),
+        };
+        writeln!(
+            output,
+            "{}{}",
+            paint.apply(color, severity),
+            paint.apply(BOLD, &format!(": {}", self.message))
+        )
+        .unwrap();
+
+        let lines = lines(source, self.newlines);
+        let mut labels: Vec<(usize, &Label, bool)> = self
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| (label.span.start.row, label, index == 0))
+            .collect();
+        labels.sort_by_key(|(row, label, _primary)| (*row, label.span.byte_range.start));
+        let width = labels
+            .iter()
+            .map(|(row, _label, _primary)| row.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = paint.apply(BLUE, &format!("{:width$} |", ""));
+
+        if let Some(primary) = self.labels.first() {
+            let Position { row, column, .. } = primary.span.start;
+            writeln!(
+                output,
+                "{:width$}{} {row}:{column}",
+                "",
+                paint.apply(BLUE, "-->"),
+            )
+            .unwrap();
+            writeln!(output, "{gutter}").unwrap();
+        }
+
+        let mut previous_row = None;
+        for (row, label, primary) in labels {
+            let line = line_of(&lines, label.span.byte_range.start);
+            let text = &source[line.clone()];
+            // `\r\n` with `Newlines::Lf`
+            let text = text.strip_suffix('\r').unwrap_or(text);
+            if previous_row != Some(row) {
+                if previous_row.is_some_and(|previous| previous + 1 < row) {
+                    writeln!(output, "{}", paint.apply(BLUE, "...")).unwrap();
+                }
+                let number = paint.apply(BLUE, &format!("{row:width$} |"));
+                writeln!(output, "{number} {}", expand_tabs(text)).unwrap();
+                previous_row = Some(row);
+            }
+            let from = char_boundary(text, label.span.byte_range.start.saturating_sub(line.start));
+            let to =
+                char_boundary(text, label.span.byte_range.end.saturating_sub(line.start)).max(from);
+            let padding = display_width(&text[..from]);
+            let length = display_width(&text[from..to]).max(1);
+            let (mark, color) = if primary { ('^', RED) } else { ('-', BLUE) };
+            let underline: String = core::iter::repeat_n(mark, length).collect();
+            let annotation = if label.message.is_empty() {
+                underline
+            } else {
+                format!("{underline} {}", label.message)
+            };
+            writeln!(
+                output,
+                "{gutter} {:padding$}{}",
+                "",
+                paint.apply(color, &annotation)
+            )
+            .unwrap();
+        }
+
+        if !self.notes.is_empty() && !self.labels.is_empty() {
+            writeln!(output, "{gutter}").unwrap();
+        }
+        for note in &self.notes {
+            writeln!(
+                output,
+                "{:width$} {} note: {note}",
+                "",
+                paint.apply(BLUE, "=")
+            )
+            .unwrap();
+        }
+
+        output
+    }
+}
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+const RESET: &str = "\x1b[0m";
+
+struct Paint(Style);
+
+impl Paint {
+    fn apply(&self, color: &str, text: &str) -> String {
+        match self.0 {
+            Style::Plain => text.to_owned(),
+            Style::Ansi => format!("{color}{text}{RESET}"),
+        }
+    }
+}
+
+const TAB_WIDTH: usize = 4;
+
+/// The byte ranges of the lines of `source`, without the line breaks
+fn lines(source: &str, newlines: Newlines) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    // Where the line ends if the line break takes several characters (like `\r\n`)
+    let mut end = None;
+    for (offset, c) in source.char_indices() {
+        let next = offset + c.len_utf8();
+        match newlines.advance(c, &source[next..]) {
+            Advance::Row => {
+                lines.push(start..end.take().unwrap_or(offset));
+                start = next;
+            }
+            Advance::Nothing => end = Some(offset),
+            Advance::Column => {}
+        }
+    }
+    lines.push(start..source.len());
+    lines
+}
+
+/// The line with `offset` in it, or the last line if `offset` is past the end
+fn line_of(lines: &[Range<usize>], offset: usize) -> Range<usize> {
+    let index = lines.partition_point(|line| line.start <= offset);
+    lines[index.saturating_sub(1)].clone()
+}
+
+/// The nearest char boundary of `text` at or before `offset`, so spans that do not fit the source
+/// are still rendered
+fn char_boundary(text: &str, offset: usize) -> usize {
+    (0..=offset.min(text.len()))
+        .rev()
+        .find(|offset| text.is_char_boundary(*offset))
+        .unwrap_or(0)
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| {
+            if c == '\t' {
+                TAB_WIDTH
+            } else {
+                visual_width(c)
+            }
+        })
+        .sum()
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{spanned, tag, take_until, Columns, PositionedString, Result};
+
+    #[test]
+    fn test_rendering_positions() {
+        let source = "let x = 1;\nlet = 2;\n";
+        let input = PositionedString::from(source);
+        let Result::Ok(_taken, rest) = take_until::<_, _, ()>(input, "= 2".chars()) else {
+            panic!("the source has a `= 2`");
+        };
+
+        let report = Report::error("unexpected `=`")
+            .label(rest.position, "expected an identifier")
+            .note("identifiers start with a letter");
+
+        assert_eq!(
+            report.render(source, Style::Plain),
+            "\
+error: unexpected `=`
+ --> 2:5
+  |
+2 | let = 2;
+  |     ^ expected an identifier
+  |
+  = note: identifiers start with a letter
+"
+        );
+    }
+
+    #[test]
+    fn test_rendering_spans() {
+        let source = "fn main() {\n\tlet x = 1\n}\n";
+        let input = PositionedString::from(source).with_columns(Columns::Visual { tab_width: 4 });
+        let Result::Ok(_taken, rest) = take_until::<_, _, ()>(input, "x".chars()) else {
+            panic!("the source has an `x`");
+        };
+        let Result::Ok((_x, span, _text), _rest) =
+            spanned::<_, ()>(rest, |rest| tag(rest, "x = 1"))
+        else {
+            panic!("the source has an `x = 1`");
+        };
+
+        let report = Report::warning("missing semicolon")
+            .label(span, "this statement")
+            .label(input.position, "");
+
+        assert_eq!(
+            report.render(source, Style::Plain),
+            "\
+warning: missing semicolon
+ --> 2:9
+  |
+1 | fn main() {
+  | -
+2 |     let x = 1
+  |         ^^^^^ this statement
+"
+        );
+    }
+
+    fn render_at(input: PositionedString, needle: &str, newlines: Newlines) -> String {
+        let Result::Ok(_taken, rest) = take_until::<_, _, ()>(input, needle.chars()) else {
+            panic!("the source has the needle");
+        };
+        Report::error("here")
+            .label(rest.position, "")
+            .with_newlines(newlines)
+            .render(input.content, Style::Plain)
+    }
+
+    #[test]
+    fn test_rendering_line_breaks() {
+        let source = "a\r\nb = 1;\r\n";
+
+        assert_eq!(
+            render_at(
+                PositionedString::from(source).with_newlines(Newlines::Ascii),
+                "=",
+                Newlines::Ascii
+            ),
+            "\
+error: here
+ --> 2:3
+  |
+2 | b = 1;
+  |   ^
+"
+        );
+
+        assert_eq!(
+            render_at(PositionedString::from(source), "=", Newlines::Lf),
+            "\
+error: here
+ --> 2:3
+  |
+2 | b = 1;
+  |   ^
+"
+        );
+
+        let source = "a\rbad\r";
+
+        assert_eq!(
+            render_at(
+                PositionedString::from(source).with_newlines(Newlines::Ascii),
+                "bad",
+                Newlines::Ascii
+            ),
+            "\
+error: here
+ --> 2:1
+  |
+2 | bad
+  | ^
+"
+        );
+    }
+
+    #[test]
+    fn test_rendering_columns() {
+        let input = PositionedString::from("x😀y").with_columns(Columns::Utf16);
+
+        assert_eq!(
+            render_at(input, "y", Newlines::Lf),
+            "\
+error: here
+ --> 1:4
+  |
+1 | x😀y
+  |    ^
+"
+        );
+    }
+
+    #[test]
+    fn test_rendering_bad_spans() {
+        let position = PositionedString::from("é").position;
+        let span = Span {
+            start: position,
+            end: position,
+            byte_range: 1..2,
+        };
+
+        assert_eq!(
+            Report::error("x")
+                .label(span, "y")
+                .render("é", Style::Plain),
+            "\
+error: x
+ --> 1:1
+  |
+1 | é
+  | ^ y
+"
+        );
+
+        let span = Span {
+            start: position,
+            end: position,
+            byte_range: 10..20,
+        };
+
+        assert_eq!(
+            Report::error("x")
+                .label(span, "y")
+                .render("é", Style::Plain),
+            "\
+error: x
+ --> 1:1
+  |
+1 | é
+  |  ^ y
+"
+        );
+    }
+
+    #[test]
+    fn test_rendering_with_colors() {
+        let rendered = Report::error("oops")
+            .label(PositionedString::from("a").position, "here")
+            .render("a", Style::Ansi);
+
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m\x1b[1m: oops\x1b[0m\n"));
+
+        assert!(rendered.contains("\x1b[1;31m^ here\x1b[0m"));
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{Input, MeasuredInput, Result, SliceInput};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// What `Utf16` does with a surrogate that is not a part of a surrogate pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Surrogates {
+    /// Decode it as U+FFFD REPLACEMENT CHARACTER
+    #[default]
+    Replace,
+    /// End the input before it. Parsers cannot tell this from the real end of the input, so run
+    /// them through `Utf16::checked()` to get `Fatal` if they reached it
+    Stop,
+}
+
+/// A surrogate that is not a part of a surrogate pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoneSurrogate {
+    pub surrogate: u16,
+    /// Index of the surrogate in the original slice
+    pub index: usize,
+}
+
+/// UTF-16 text, decoded into `char`s one at a time. Clones share the record of the lone surrogate
+/// that `Surrogates::Stop` stopped at, see `checked()`
+#[derive(Debug, Clone)]
+pub struct Utf16<'a> {
+    pub content: &'a [u16],
+    /// Index of the first unit of `content` in the original slice
+    pub index: usize,
+    pub surrogates: Surrogates,
+    lone_surrogate: Rc<Cell<Option<LoneSurrogate>>>,
+}
+
+/// Inputs are equal if they are at the same place of the same text
+impl PartialEq for Utf16<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.content, self.index, self.surrogates)
+            == (other.content, other.index, other.surrogates)
+    }
+}
+
+impl Eq for Utf16<'_> {}
+
+impl<'a> Utf16<'a> {
+    pub fn with_surrogates(self, surrogates: Surrogates) -> Self {
+        Self { surrogates, ..self }
+    }
+
+    /// Takes the lone surrogate that a parser stopped at with `Surrogates::Stop`, if there was one
+    pub fn take_lone_surrogate(&self) -> Option<LoneSurrogate> {
+        self.lone_surrogate.take()
+    }
+
+    /// Turns `result` into `Fatal` if a parser stopped at a lone surrogate while getting it
+    pub fn checked<T, F: From<LoneSurrogate>>(
+        &self,
+        result: Result<T, Self, F>,
+    ) -> Result<T, Self, F> {
+        match self.take_lone_surrogate() {
+            Some(lone_surrogate) => Result::Fatal(lone_surrogate.into()),
+            None => result,
+        }
+    }
+
+    fn decode(&self) -> Option<(core::result::Result<char, u16>, Self)> {
+        let decoded = char::decode_utf16(self.content.iter().copied()).next()?;
+        let (decoded, length) = match decoded {
+            core::result::Result::Ok(c) => (core::result::Result::Ok(c), c.len_utf16()),
+            core::result::Result::Err(error) => {
+                (core::result::Result::Err(error.unpaired_surrogate()), 1)
+            }
+        };
+        Some((
+            decoded,
+            Self {
+                content: &self.content[length..],
+                index: self.index + length,
+                surrogates: self.surrogates,
+                lone_surrogate: Rc::clone(&self.lone_surrogate),
+            },
+        ))
+    }
+}
+
+impl<'a> From<&'a [u16]> for Utf16<'a> {
+    fn from(content: &'a [u16]) -> Self {
+        Self {
+            content,
+            index: 0,
+            surrogates: Surrogates::default(),
+            lone_surrogate: Rc::default(),
+        }
+    }
+}
+
+impl<'a> Input for Utf16<'a> {
+    type Part = char;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        match self.decode()? {
+            (core::result::Result::Ok(c), rest) => Some((c, rest)),
+            (core::result::Result::Err(surrogate), rest) => match self.surrogates {
+                Surrogates::Replace => Some((char::REPLACEMENT_CHARACTER, rest)),
+                Surrogates::Stop => {
+                    self.lone_surrogate.set(Some(LoneSurrogate {
+                        surrogate,
+                        index: self.index,
+                    }));
+                    None
+                }
+            },
+        }
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl MeasuredInput for Utf16<'_> {
+    fn measure(&self) -> usize {
+        self.content.len()
+    }
+}
+
+impl<'a> SliceInput for Utf16<'a> {
+    type Slice = Utf16<'a>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        Self {
+            content: self.content.slice_to(&rest.content),
+            ..self.clone()
+        }
+    }
+}
+
+/// Takes one `char`, failing with `Fatal(lone_surrogate(surrogate))` on a surrogate that is not a
+/// part of a surrogate pair, no matter what `input.surrogates` says
+pub fn utf16_char<'a, F>(
+    input: Utf16<'a>,
+    lone_surrogate: impl FnOnce(u16) -> F,
+) -> Result<char, Utf16<'a>, F> {
+    match input.decode() {
+        Some((core::result::Result::Ok(c), rest)) => Result::Ok(c, rest),
+        Some((core::result::Result::Err(surrogate), _rest)) => {
+            Result::Fatal(lone_surrogate(surrogate))
+        }
+        None => Result::Err,
+    }
+}
+
+/// ISO-8859-1 text: every byte is the `char` with the same number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latin1<'a>(pub &'a [u8]);
+
+impl<'a> Input for Latin1<'a> {
+    type Part = char;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        self.0
+            .split_first()
+            .map(|(byte, rest)| (char::from(*byte), Self(rest)))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl MeasuredInput for Latin1<'_> {
+    fn measure(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'a> SliceInput for Latin1<'a> {
+    type Slice = Latin1<'a>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        Self(self.0.slice_to(&rest.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collect_repeating, literal, one_part, take_while, CollResult};
+
+    fn utf16(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    /// A char-based grammar that works on any of the inputs
+    fn word<I: SliceInput<Part = char> + Clone>(input: I) -> Result<I::Slice, I, ()> {
+        take_while(input, |c| c.is_alphabetic())
+    }
+
+    fn text<I: Input<Part = char> + Clone>(input: I) -> String {
+        match collect_repeating(String::new(), input, |rest: &I| {
+            one_part::<_, ()>(rest.clone())
+        }) {
+            CollResult::Ok(text, _rest) => text,
+            CollResult::Fatal(()) => unreachable!("`one_part()` does not fail with `Fatal`"),
+        }
+    }
+
+    #[test]
+    fn test_utf16() {
+        let content = utf16("naïve 🦀!");
+        let input = Utf16::from(&content[..]);
+
+        let Result::Ok(taken, rest) = word(input.clone()) else {
+            panic!("`take_while()` does not fail");
+        };
+
+        assert_eq!(taken.content, utf16("naïve"));
+
+        assert!(matches!(
+            literal::<_, _, ()>(rest, [' ', '🦀']),
+            Result::Ok((), rest) if rest.content == utf16("!")
+        ));
+
+        assert_eq!(text(input), "naïve 🦀!");
+    }
+
+    #[test]
+    fn test_lone_surrogates() {
+        let content = [0x61, 0xD800, 0x62, 0xDC00];
+        let input = Utf16::from(&content[..]);
+
+        assert_eq!(text(input.clone()), "a\u{FFFD}b\u{FFFD}");
+
+        assert_eq!(input.take_lone_surrogate(), None);
+
+        let stopping = || Utf16::from(&content[..]).with_surrogates(Surrogates::Stop);
+
+        assert_eq!(text(stopping()), "a");
+
+        let input = stopping();
+
+        assert_eq!(
+            input.checked(take_while(input.clone(), |c| c.is_alphabetic())),
+            Result::Fatal(LoneSurrogate {
+                surrogate: 0xD800,
+                index: 1
+            })
+        );
+
+        assert_eq!(
+            input.checked(literal(input.clone(), ['a', 'b'])),
+            Result::Fatal(LoneSurrogate {
+                surrogate: 0xD800,
+                index: 1
+            })
+        );
+
+        assert!(matches!(
+            input.checked::<_, LoneSurrogate>(one_part(input.clone())),
+            Result::Ok('a', rest) if rest.content == [0xD800, 0x62, 0xDC00]
+        ));
+
+        let content = utf16("hello world");
+        let content = [&content[..], &[0xD800]].concat();
+        let input = Utf16::from(&content[..]).with_surrogates(Surrogates::Stop);
+
+        assert_eq!(
+            input.checked::<_, LoneSurrogate>(literal(input.clone(), ['x'])),
+            Result::Err
+        );
+
+        let Result::Ok('a', rest) = utf16_char(stopping(), |surrogate| surrogate) else {
+            panic!("the input starts with `a`");
+        };
+
+        assert_eq!(
+            utf16_char(rest, |surrogate| surrogate),
+            Result::Fatal(0xD800)
+        );
+
+        assert_eq!(
+            utf16_char(Utf16::from(&[][..]), |surrogate| surrogate),
+            Result::Err
+        );
+    }
+
+    #[test]
+    fn test_latin1() {
+        let input = Latin1(b"caf\xe9 \xbfqu\xe9?");
+
+        assert!(matches!(
+            word(input),
+            Result::Ok(taken, _rest) if taken == Latin1(b"caf\xe9")
+        ));
+
+        assert_eq!(text(input), "café ¿qué?");
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{Input, Position, PositionedSlice, PositionedString};
+use core::cmp::{Ordering, Reverse};
+
+/// Inputs that know how far into the source they are
+pub trait Located {
+    /// Locations that are further into the source are bigger
+    type Location: Ord;
+
+    fn location(&self) -> Self::Location;
+}
+
+impl Located for &str {
+    type Location = Reverse<usize>;
+
+    fn location(&self) -> Self::Location {
+        Reverse(self.len())
+    }
+}
+
+impl<T> Located for &[T] {
+    type Location = Reverse<usize>;
+
+    fn location(&self) -> Self::Location {
+        Reverse(self.len())
+    }
+}
+
+impl Located for PositionedString<'_> {
+    type Location = Position;
+
+    fn location(&self) -> Self::Location {
+        self.position
+    }
+}
+
+impl<T> Located for PositionedSlice<'_, T> {
+    type Location = usize;
+
+    fn location(&self) -> Self::Location {
+        self.index
+    }
+}
+
+/// "Expected one of `labels` at `location`"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected<P, L> {
+    pub location: P,
+    pub labels: Vec<L>,
+}
+
+impl<P, L> Expected<P, L> {
+    pub fn new(location: P, label: L) -> Self {
+        Self {
+            location,
+            labels: vec![label],
+        }
+    }
+}
+
+/// Errors that can be combined when all alternatives fail
+pub trait Merge {
+    fn merge(self, other: Self) -> Self;
+}
+
+/// Keeps the error that went further into the source, or all the labels if both errors are at the
+/// same location
+impl<P: Ord, L: PartialEq> Merge for Expected<P, L> {
+    fn merge(mut self, other: Self) -> Self {
+        match self.location.cmp(&other.location) {
+            Ordering::Greater => self,
+            Ordering::Less => other,
+            Ordering::Equal => {
+                for label in other.labels {
+                    if !self.labels.contains(&label) {
+                        self.labels.push(label);
+                    }
+                }
+                self
+            }
+        }
+    }
+}
+
+/// `parco::Result` that carries an error in the recoverable variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Result<T, I, E, F> {
+    /// Parsing completed successfully
+    Ok(T, I),
+    /// Recoverable error meaning "input cannot be parsed with the current parser"
+    Err(E),
+    /// Unrecoverable error meaning "input cannot be parsed with any parser"
+    Fatal(F),
+}
+
+use self::Result::{Err, Fatal, Ok};
+
+impl<T, I, E, F> Result<T, I, E, F> {
+    pub fn and<OT, OI>(self, f: impl FnOnce(T, I) -> Result<OT, OI, E, F>) -> Result<OT, OI, E, F> {
+        match self {
+            Ok(result, rest) => f(result, rest),
+            Err(e) => Err(e),
+            Fatal(e) => Fatal(e),
+        }
+    }
+
+    /// If both parsers fail with `Err`, their errors are merged
+    pub fn or(self, f: impl FnOnce() -> Self) -> Self
+    where
+        E: Merge,
+    {
+        match self {
+            Ok(result, rest) => Ok(result, rest),
+            Err(e) => match f() {
+                Err(other) => Err(e.merge(other)),
+                result => result,
+            },
+            Fatal(e) => Fatal(e),
+        }
+    }
+
+    pub fn map<O>(self, f: impl FnOnce(T) -> O) -> Result<O, I, E, F> {
+        match self {
+            Ok(result, rest) => Ok(f(result), rest),
+            Err(e) => Err(e),
+            Fatal(e) => Fatal(e),
+        }
+    }
+
+    pub fn map_err<O>(self, f: impl FnOnce(E) -> O) -> Result<T, I, O, F> {
+        match self {
+            Ok(result, rest) => Ok(result, rest),
+            Err(e) => Err(f(e)),
+            Fatal(e) => Fatal(e),
+        }
+    }
+
+    /// Turns `Err` into `Fatal`, see `parco::Result::cut()`
+    pub fn cut(self, f: impl FnOnce(E) -> F) -> Self {
+        match self {
+            Ok(result, rest) => Ok(result, rest),
+            Err(e) => Fatal(f(e)),
+            Fatal(e) => Fatal(e),
+        }
+    }
+
+    /// Forgets the error of `Err`
+    pub fn plain(self) -> crate::Result<T, I, F> {
+        match self {
+            Ok(result, rest) => crate::Result::Ok(result, rest),
+            Err(_e) => crate::Result::Err,
+            Fatal(e) => crate::Result::Fatal(e),
+        }
+    }
+}
+
+/// Runs a plain parser, turning its `Err` into "expected `label`" at the start of `input`
+pub fn expect<I: Located, L, T, F>(
+    input: I,
+    label: L,
+    parser: impl FnOnce(I) -> crate::Result<T, I, F>,
+) -> Result<T, I, Expected<I::Location, L>, F> {
+    let location = input.location();
+    match parser(input) {
+        crate::Result::Ok(result, rest) => Ok(result, rest),
+        crate::Result::Err => Err(Expected::new(location, label)),
+        crate::Result::Fatal(e) => Fatal(e),
+    }
+}
+
+pub fn one_part<I: Input + Located, L, F>(
+    input: I,
+    label: L,
+) -> Result<I::Part, I, Expected<I::Location, L>, F> {
+    expect(input, label, crate::one_part)
+}
+
+pub fn one_matching_part<I: Input + Located, L, F>(
+    input: I,
+    label: L,
+    f: impl FnOnce(&I::Part) -> bool,
+) -> Result<I::Part, I, Expected<I::Location, L>, F> {
+    expect(input, label, |input| crate::one_matching_part(input, f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Res<'s, T> = Result<T, PositionedString<'s>, Expected<Position, &'static str>, ()>;
+
+    fn digit(input: PositionedString) -> Res<char> {
+        one_matching_part(input, "digit", |c| c.is_ascii_digit())
+    }
+
+    fn letter(input: PositionedString) -> Res<char> {
+        one_matching_part(input, "letter", |c| c.is_alphabetic())
+    }
+
+    fn pair(input: PositionedString) -> Res<(char, char)> {
+        letter(input).and(|first, rest| digit(rest).map(|second| (first, second)))
+    }
+
+    #[test]
+    fn test_recording_expectations() {
+        let input = PositionedString::from("a?");
+
+        assert_eq!(digit(input), Err(Expected::new(input.position, "digit")));
+
+        assert!(matches!(letter(input), Ok('a', _)));
+
+        assert_eq!(
+            one_part::<_, _, ()>("", "anything"),
+            Err(Expected::new(Reverse(0), "anything"))
+        );
+    }
+
+    #[test]
+    fn test_merging_expectations() {
+        let input = PositionedString::from("?");
+
+        assert_eq!(
+            digit(input).or(|| letter(input)).or(|| digit(input)),
+            Err(Expected {
+                location: input.position,
+                labels: vec!["digit", "letter"]
+            })
+        );
+
+        let input = PositionedString::from("a?");
+
+        let Err(error) = pair(input).or(|| digit(input).map(|c| (c, c))) else {
+            panic!("both alternatives should fail");
+        };
+
+        assert_eq!(error.labels, vec!["digit"]);
+
+        assert_eq!(error.location.column, 2);
+
+        let Err(error) = digit(input).map(|c| (c, c)).or(|| pair(input)) else {
+            panic!("both alternatives should fail");
+        };
+
+        assert_eq!(error.location.column, 2);
+    }
+
+    #[test]
+    fn test_cutting() {
+        let input = PositionedString::from("a?");
+
+        assert_eq!(pair(input).cut(|_error| ()), Fatal(()));
+
+        assert!(matches!(letter(input).cut(|_error| ()), Ok('a', _)));
+    }
+
+    #[test]
+    fn test_plain_conversion() {
+        assert!(matches!(
+            digit("1".into()).plain(),
+            crate::Result::Ok('1', _)
+        ));
+
+        assert_eq!(digit("?".into()).plain(), crate::Result::Err);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{Advance, Input, MeasuredInput, Newlines, Position, SliceInput};
+
+/// Text split into grapheme clusters (what users see as characters), with positions whose columns
+/// count grapheme clusters.
+///
+/// The segmentation is an approximation of the extended grapheme clusters of Unicode: it keeps
+/// `\r\n`, combining marks, variation selectors, emoji modifiers and tags, emoji joined with a
+/// zero-width joiner, flags (pairs of regional indicators) and Hangul syllables made of jamo
+/// together, but does not know every character property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Graphemes<'s> {
+    pub content: &'s str,
+    pub position: Position,
+    pub newlines: Newlines,
+}
+
+impl<'s> Graphemes<'s> {
+    pub fn with_newlines(self, newlines: Newlines) -> Self {
+        Self { newlines, ..self }
+    }
+}
+
+impl<'a> From<&'a str> for Graphemes<'a> {
+    fn from(content: &'a str) -> Self {
+        Self {
+            content,
+            position: Position {
+                row: 1,
+                column: 1,
+                offset: 0,
+                char_offset: 0,
+            },
+            newlines: Newlines::default(),
+        }
+    }
+}
+
+impl<'s> Input for Graphemes<'s> {
+    type Part = &'s str;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        let (grapheme, rest) = split_grapheme(self.content)?;
+        let offset = self.position.offset + grapheme.len();
+        let char_offset = self.position.char_offset + grapheme.chars().count();
+        // Line breaks do not take extending characters, so a line break is the last character of
+        // its grapheme (`\n` in `\r\n`)
+        let last = grapheme.chars().next_back()?;
+        let position = match self.newlines.advance(last, "") {
+            Advance::Row => Position {
+                row: self.position.row + 1,
+                column: 1,
+                offset,
+                char_offset,
+            },
+            Advance::Column | Advance::Nothing => Position {
+                row: self.position.row,
+                column: self.position.column + 1,
+                offset,
+                char_offset,
+            },
+        };
+        Some((
+            grapheme,
+            Self {
+                content: rest,
+                position,
+                newlines: self.newlines,
+            },
+        ))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl MeasuredInput for Graphemes<'_> {
+    fn measure(&self) -> usize {
+        self.content.len()
+    }
+}
+
+impl<'s> SliceInput for Graphemes<'s> {
+    type Slice = Graphemes<'s>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        Self {
+            content: self.content.slice_to(&rest.content),
+            ..*self
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    Pictographic,
+    HangulL,
+    HangulV,
+    HangulT,
+    HangulLv,
+    HangulLvt,
+    Other,
+}
+
+fn kind(c: char) -> Kind {
+    match c as u32 {
+        0x000D => Kind::Cr,
+        0x000A => Kind::Lf,
+        0x0000..=0x001F | 0x007F..=0x009F | 0x2028 | 0x2029 => Kind::Control,
+        0x200D => Kind::Zwj,
+        0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0670
+        | 0x06D6..=0x06DC
+        | 0x06DF..=0x06E4
+        | 0x06E7..=0x06E8
+        | 0x06EA..=0x06ED
+        | 0x0900..=0x0903
+        | 0x093A..=0x093C
+        | 0x093E..=0x094F
+        | 0x0951..=0x0957
+        | 0x0962..=0x0963
+        | 0x0E31
+        | 0x0E34..=0x0E3A
+        | 0x0E47..=0x0E4E
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200C
+        | 0x20D0..=0x20FF
+        | 0x302A..=0x302F
+        | 0x3099..=0x309A
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF
+        | 0xE0020..=0xE007F
+        | 0xE0100..=0xE01EF => Kind::Extend,
+        0x1F1E6..=0x1F1FF => Kind::RegionalIndicator,
+        0x00A9
+        | 0x00AE
+        | 0x203C
+        | 0x2049
+        | 0x2122
+        | 0x2139
+        | 0x2194..=0x2199
+        | 0x21A9..=0x21AA
+        | 0x231A..=0x231B
+        | 0x2328
+        | 0x23CF
+        | 0x23E9..=0x23F3
+        | 0x23F8..=0x23FA
+        | 0x24C2
+        | 0x25AA..=0x25AB
+        | 0x25B6
+        | 0x25C0
+        | 0x25FB..=0x25FE
+        | 0x2600..=0x27BF
+        | 0x2934..=0x2935
+        | 0x2B05..=0x2B07
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55
+        | 0x3030
+        | 0x303D
+        | 0x3297
+        | 0x3299
+        | 0x1F000..=0x1FAFF => Kind::Pictographic,
+        0x1100..=0x115F | 0xA960..=0xA97C => Kind::HangulL,
+        0x1160..=0x11A7 | 0xD7B0..=0xD7C6 => Kind::HangulV,
+        0x11A8..=0x11FF | 0xD7CB..=0xD7FB => Kind::HangulT,
+        syllable @ 0xAC00..=0xD7A3 => {
+            if (syllable - 0xAC00) % 28 == 0 {
+                Kind::HangulLv
+            } else {
+                Kind::HangulLvt
+            }
+        }
+        _ => Kind::Other,
+    }
+}
+
+/// Whether there is no grapheme cluster boundary between `previous` and `next`
+fn joins(previous: Kind, next: Kind) -> bool {
+    use Kind::*;
+    match (previous, next) {
+        (Cr, Lf) => true,
+        (Cr | Lf | Control, _) | (_, Cr | Lf | Control) => false,
+        (HangulL, HangulL | HangulV | HangulLv | HangulLvt) => true,
+        (HangulLv | HangulV, HangulV | HangulT) => true,
+        (HangulLvt | HangulT, HangulT) => true,
+        (_, Extend | Zwj) => true,
+        _ => false,
+    }
+}
+
+/// Splits `s` into its first grapheme cluster and the rest
+pub fn split_grapheme(s: &str) -> Option<(&str, &str)> {
+    let mut chars = s.char_indices();
+    let (_, first) = chars.next()?;
+    let mut previous = kind(first);
+    let mut regional_indicators = usize::from(previous == Kind::RegionalIndicator);
+    // Whether the cluster is an emoji followed by extending characters, which a zero-width joiner
+    // can join with another emoji
+    let mut emoji = previous == Kind::Pictographic;
+    for (index, c) in chars {
+        let next = kind(c);
+        let joined = match (previous, next) {
+            (Kind::Zwj, Kind::Pictographic) => emoji,
+            (Kind::RegionalIndicator, Kind::RegionalIndicator) => regional_indicators % 2 == 1,
+            _ => joins(previous, next),
+        };
+        if !joined {
+            return Some(s.split_at(index));
+        }
+        if next == Kind::RegionalIndicator {
+            regional_indicators += 1;
+        }
+        emoji = match next {
+            Kind::Pictographic => true,
+            Kind::Extend => emoji,
+            _ => emoji && next == Kind::Zwj,
+        };
+        previous = next;
+    }
+    Some((s, ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collect_at_most, literal, one_part, take_while, Result};
+
+    fn graphemes(s: &str) -> Vec<&str> {
+        let mut graphemes = Vec::new();
+        let mut rest = s;
+        while let Some((grapheme, next)) = split_grapheme(rest) {
+            graphemes.push(grapheme);
+            rest = next;
+        }
+        graphemes
+    }
+
+    #[test]
+    fn test_splitting() {
+        assert_eq!(graphemes("abc"), vec!["a", "b", "c"]);
+
+        assert_eq!(graphemes("e\u{301}x"), vec!["e\u{301}", "x"]);
+
+        assert_eq!(graphemes("a\r\nb\n\r"), vec!["a", "\r\n", "b", "\n", "\r"]);
+
+        assert_eq!(graphemes("👍🏽!"), vec!["👍🏽", "!"]);
+
+        assert_eq!(graphemes("👩‍👩‍👧‍👦x"), vec!["👩‍👩‍👧‍👦", "x"]);
+
+        assert_eq!(graphemes("a\u{200D}👍"), vec!["a\u{200D}", "👍"]);
+
+        assert_eq!(graphemes("🇺🇦🇯🇵🇺"), vec!["🇺🇦", "🇯🇵", "🇺"]);
+
+        assert_eq!(graphemes("❤\u{FE0F}"), vec!["❤\u{FE0F}"]);
+
+        assert_eq!(
+            graphemes("\u{1112}\u{1161}\u{11AB}한"),
+            vec!["\u{1112}\u{1161}\u{11AB}", "한"]
+        );
+
+        assert_eq!(graphemes("\u{301}a"), vec!["\u{301}", "a"]);
+
+        assert_eq!(graphemes(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_positions() {
+        let input = Graphemes::from("👩‍👩‍👧‍👦é\r\nx");
+
+        let Result::Ok(_taken, rest) = literal::<_, _, ()>(input, ["👩‍👩‍👧‍👦", "é"])
+        else {
+            panic!("the input starts with these graphemes");
+        };
+
+        assert_eq!(
+            rest.position,
+            Position {
+                row: 1,
+                column: 3,
+                offset: "👩‍👩‍👧‍👦é".len(),
+                char_offset: 8,
+            }
+        );
+
+        let Some((_newline, rest)) = rest.take_one_part() else {
+            panic!("the input goes on");
+        };
+
+        assert_eq!((rest.position.row, rest.position.column), (2, 1));
+
+        let Some((_cr, rest)) = Graphemes::from("\rx")
+            .with_newlines(Newlines::Ascii)
+            .take_one_part()
+        else {
+            panic!("the input is not empty");
+        };
+
+        assert_eq!((rest.position.row, rest.position.column), (2, 1));
+    }
+
+    #[test]
+    fn test_limiting_length() {
+        let name = Graphemes::from("Zoë👍🏽 and others");
+
+        let Result::Ok(word, _rest) = take_while::<_, ()>(name, |g| *g != " ") else {
+            panic!("`take_while()` does not fail");
+        };
+
+        assert_eq!(word.content, "Zoë👍🏽");
+
+        assert_eq!(word.take_one_part().map(|(z, _rest)| z), Some("Z"));
+
+        assert!(matches!(
+            collect_at_most(Vec::new(), name, 4, |rest: &Graphemes| {
+                one_part::<_, ()>(*rest)
+            }),
+            Result::Ok(graphemes, rest) if graphemes.len() == 4 && rest.content == " and others"
+        ));
+    }
+}
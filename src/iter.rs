@@ -0,0 +1,256 @@
+use crate::{Input, MeasuredInput, SliceInput};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// Items of an iterator that is cloned every time an item is taken, so it should be cheap to clone
+/// (like most iterators over collections). For other iterators, use `BufferedIter`
+#[derive(Debug, Clone)]
+pub struct IterInput<It> {
+    iter: It,
+    index: usize,
+}
+
+impl<It: Iterator + Clone> IterInput<It> {
+    pub fn new(iter: impl IntoIterator<IntoIter = It>) -> Self {
+        Self {
+            iter: iter.into_iter(),
+            index: 0,
+        }
+    }
+
+    /// How many items were taken from the beginning of the iterator to get to this input
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<It: Iterator + Clone> Input for IterInput<It> {
+    type Part = It::Item;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        let mut iter = self.iter.clone();
+        iter.next().map(|item| {
+            (
+                item,
+                Self {
+                    iter,
+                    index: self.index + 1,
+                },
+            )
+        })
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl<It: Iterator + Clone> MeasuredInput for IterInput<It> {
+    fn measure(&self) -> usize {
+        usize::MAX - self.index
+    }
+}
+
+impl<It: Iterator + Clone> SliceInput for IterInput<It> {
+    type Slice = Vec<It::Item>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        self.iter.clone().take(rest.index - self.index).collect()
+    }
+}
+
+struct Items<It: Iterator> {
+    iter: It,
+    taken: Vec<It::Item>,
+}
+
+/// Items of any iterator. The items are stored when they are first taken, so the clones of the input
+/// share them and can go back to any of them
+pub struct BufferedIter<It: Iterator> {
+    items: Rc<RefCell<Items<It>>>,
+    index: usize,
+}
+
+impl<It: Iterator> Clone for BufferedIter<It> {
+    fn clone(&self) -> Self {
+        Self {
+            items: Rc::clone(&self.items),
+            index: self.index,
+        }
+    }
+}
+
+impl<It: Iterator> fmt::Debug for BufferedIter<It> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufferedIter")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<It: Iterator> BufferedIter<It> {
+    pub fn new(iter: impl IntoIterator<IntoIter = It>) -> Self {
+        Self {
+            items: Rc::new(RefCell::new(Items {
+                iter: iter.into_iter(),
+                taken: Vec::new(),
+            })),
+            index: 0,
+        }
+    }
+
+    /// How many items were taken from the beginning of the iterator to get to this input
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<It: Iterator> Input for BufferedIter<It>
+where
+    It::Item: Clone,
+{
+    type Part = It::Item;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        let mut items = self.items.borrow_mut();
+        if self.index == items.taken.len() {
+            let item = items.iter.next()?;
+            items.taken.push(item);
+        }
+        Some((
+            items.taken[self.index].clone(),
+            Self {
+                items: Rc::clone(&self.items),
+                index: self.index + 1,
+            },
+        ))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl<It: Iterator> MeasuredInput for BufferedIter<It>
+where
+    It::Item: Clone,
+{
+    fn measure(&self) -> usize {
+        usize::MAX - self.index
+    }
+}
+
+impl<It: Iterator> SliceInput for BufferedIter<It>
+where
+    It::Item: Clone,
+{
+    type Slice = Vec<It::Item>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        self.items.borrow().taken[self.index..rest.index].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{collect_repeating, one_matching_part, one_part, take_while, CollResult, Result};
+    use std::cell::Cell;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Start(&'static str),
+        Text(&'static str),
+        End,
+    }
+
+    /// Collects the texts of an element; the repetition backtracks when it reaches the end event
+    fn element<I: Input<Part = Event> + Clone>(input: I) -> Result<Vec<&'static str>, I, ()> {
+        one_matching_part(input, |event| matches!(event, Event::Start(_))).and(|_start, rest| {
+            collect_repeating(Vec::new(), rest, |rest: &I| {
+                one_part(rest.clone()).and(|event, rest| match event {
+                    Event::Text(text) => Result::Ok(text, rest),
+                    _ => Result::Err,
+                })
+            })
+            .norm()
+            .and(|texts, rest| {
+                one_matching_part(rest, |event| *event == Event::End).map(|_end| texts)
+            })
+        })
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            Event::Start("p"),
+            Event::Text("a"),
+            Event::Text("b"),
+            Event::End,
+            Event::Text("c"),
+        ]
+    }
+
+    #[test]
+    fn test_cloned_iterator() {
+        let events = events();
+        let input = IterInput::new(events.iter().cloned());
+
+        let Result::Ok(texts, rest) = element(input.clone()) else {
+            panic!("the events start with an element");
+        };
+
+        assert_eq!(texts, vec!["a", "b"]);
+
+        assert_eq!(rest.index(), 4);
+
+        assert!(matches!(
+            one_part::<_, ()>(input),
+            Result::Ok(Event::Start("p"), _)
+        ));
+
+        let Result::Ok(taken, _rest) =
+            take_while::<_, ()>(IterInput::new(1..), |number| *number < 4)
+        else {
+            panic!("`take_while()` does not fail");
+        };
+
+        assert_eq!(taken, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_buffered_iterator() {
+        let pulled = Cell::new(0);
+        let input = BufferedIter::new(events().into_iter().inspect(|_event| {
+            pulled.set(pulled.get() + 1);
+        }));
+
+        let Result::Ok(texts, rest) = element(input.clone()) else {
+            panic!("the events start with an element");
+        };
+
+        assert_eq!(texts, vec!["a", "b"]);
+
+        assert_eq!(pulled.get(), 4);
+
+        assert!(matches!(element(input.clone()), Result::Ok(texts, _) if texts == vec!["a", "b"]));
+
+        assert_eq!(pulled.get(), 4);
+
+        let Result::Ok(taken, _rest) = take_while::<_, ()>(input, |event| *event != Event::End)
+        else {
+            panic!("`take_while()` does not fail");
+        };
+
+        assert_eq!(taken, events()[..3]);
+
+        assert!(matches!(
+            collect_repeating(Vec::new(), rest, |rest: &BufferedIter<_>| {
+                one_part::<_, ()>(rest.clone())
+            }),
+            CollResult::Ok(events, _) if events == vec![Event::Text("c")]
+        ));
+
+        assert_eq!(pulled.get(), 5);
+    }
+}
@@ -1,9 +1,39 @@
+pub mod bytes;
+pub mod diagnostics;
+pub mod encodings;
+pub mod expected;
+#[cfg(feature = "graphemes")]
+pub mod graphemes;
+pub mod iter;
+pub mod memo;
+pub mod parser;
+pub mod pratt;
+pub mod read;
+pub mod recovery;
+pub mod streaming;
+
+pub use parser::Parser;
+
 pub trait Input {
     type Part;
 
     fn take_one_part(&self) -> Option<(Self::Part, Self)>
     where
         Self: Sized;
+
+    /// Anything that gets smaller when parts are taken (like the count of the remaining parts or
+    /// bytes), if the input can measure it. Repeating combinators use it to stop parsers that
+    /// succeed without consuming anything from looping forever
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Inputs that can always measure what `Input::remaining()` returns, which is needed where going
+/// on without it would never end
+pub trait MeasuredInput: Input {
+    /// The same as `Input::remaining()`
+    fn measure(&self) -> usize;
 }
 
 impl Input for &str {
@@ -13,6 +43,34 @@ impl Input for &str {
         let mut chars = self.chars();
         chars.next().map(|c| (c, chars.as_str()))
     }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl MeasuredInput for &str {
+    fn measure(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: Clone> Input for &[T] {
+    type Part = T;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        self.split_first().map(|(part, rest)| (part.clone(), rest))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl<T: Clone> MeasuredInput for &[T] {
+    fn measure(&self) -> usize {
+        self.len()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -20,19 +78,202 @@ pub struct Position {
     pub row: usize,
     /// Column
     pub column: usize,
+    /// Byte offset from the beginning of the source
+    pub offset: usize,
+    /// Char offset from the beginning of the source
+    pub char_offset: usize,
+}
+
+/// Positions are ordered by how far into the source they are
+impl PartialOrd for Position {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Position {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.offset, self.char_offset, self.row, self.column).cmp(&(
+            other.offset,
+            other.char_offset,
+            other.row,
+            other.column,
+        ))
+    }
+}
+
+/// The region of the source between two positions
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Span {
+    pub start: Position,
+    /// The position right after the last consumed part
+    pub end: Position,
+    pub byte_range: core::ops::Range<usize>,
+}
+
+impl From<Position> for Span {
+    fn from(position: Position) -> Self {
+        Self {
+            start: position,
+            end: position,
+            byte_range: position.offset..position.offset,
+        }
+    }
+}
+
+/// How `PositionedString` counts columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Columns {
+    /// One column per `char`
+    #[default]
+    Chars,
+    /// One column per UTF-8 byte
+    Utf8,
+    /// One column per UTF-16 code unit, like LSP does by default
+    Utf16,
+    /// Columns as a terminal displays them: tabs go to the next tab stop, wide characters take
+    /// two columns and combining characters take none
+    Visual { tab_width: usize },
+}
+
+impl Columns {
+    fn next_column(self, column: usize, c: char) -> usize {
+        match self {
+            Self::Chars => column + 1,
+            Self::Utf8 => column + c.len_utf8(),
+            Self::Utf16 => column + c.len_utf16(),
+            Self::Visual { tab_width } => {
+                if c == '\t' {
+                    match (column - 1).checked_div(tab_width) {
+                        Some(stops) => (stops + 1) * tab_width + 1,
+                        None => column,
+                    }
+                } else {
+                    column + visual_width(c)
+                }
+            }
+        }
+    }
+}
+
+/// An approximation of how many terminal cells `c` takes
+fn visual_width(c: char) -> usize {
+    match c as u32 {
+        0x0000..=0x001F
+        | 0x007F..=0x009F
+        | 0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0xE0100..=0xE01EF => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Which sequences `PositionedString` considers line breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newlines {
+    /// Only `\n`
+    #[default]
+    Lf,
+    /// `\n`, `\r\n` and a lone `\r`
+    Ascii,
+    /// `\n`, `\r\n`, a lone `\r`, vertical tab, form feed, `\u{85}` (NEL), `\u{2028}` (line
+    /// separator) and `\u{2029}` (paragraph separator)
+    Unicode,
+}
+
+enum Advance {
+    Column,
+    Row,
+    /// The first half of `\r\n`, the position changes only after `\n`
+    Nothing,
+}
+
+impl Newlines {
+    fn advance(self, c: char, rest: &str) -> Advance {
+        match (self, c) {
+            (_, '\n') => Advance::Row,
+            (Self::Ascii | Self::Unicode, '\r') => {
+                if rest.starts_with('\n') {
+                    Advance::Nothing
+                } else {
+                    Advance::Row
+                }
+            }
+            (Self::Unicode, '\u{0B}' | '\u{0C}' | '\u{85}' | '\u{2028}' | '\u{2029}') => {
+                Advance::Row
+            }
+            _ => Advance::Column,
+        }
+    }
+}
+
+/// Settings of position tracking in `PositionedString`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tracking {
+    pub columns: Columns,
+    pub newlines: Newlines,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PositionedString<'s> {
     pub content: &'s str,
     pub position: Position,
+    pub tracking: Tracking,
+}
+
+impl<'s> PositionedString<'s> {
+    pub fn with_columns(self, columns: Columns) -> Self {
+        Self {
+            tracking: Tracking {
+                columns,
+                ..self.tracking
+            },
+            ..self
+        }
+    }
+
+    pub fn with_newlines(self, newlines: Newlines) -> Self {
+        Self {
+            tracking: Tracking {
+                newlines,
+                ..self.tracking
+            },
+            ..self
+        }
+    }
 }
 
 impl<'a> From<&'a str> for PositionedString<'a> {
     fn from(content: &'a str) -> Self {
         Self {
             content,
-            position: Position { row: 1, column: 1 },
+            position: Position {
+                row: 1,
+                column: 1,
+                offset: 0,
+                char_offset: 0,
+            },
+            tracking: Tracking::default(),
         }
     }
 }
@@ -47,24 +288,145 @@ impl<'s> Input for PositionedString<'s> {
                 c,
                 Self {
                     content: chars.as_str(),
-                    position: if c == '\n' {
-                        Position {
+                    position: match self.tracking.newlines.advance(c, chars.as_str()) {
+                        Advance::Row => Position {
                             row: self.position.row + 1,
                             column: 1,
-                        }
-                    } else {
-                        Position {
+                            offset: self.position.offset + c.len_utf8(),
+                            char_offset: self.position.char_offset + 1,
+                        },
+                        Advance::Column => Position {
                             row: self.position.row,
-                            column: self.position.column + 1,
-                        }
+                            column: self.tracking.columns.next_column(self.position.column, c),
+                            offset: self.position.offset + c.len_utf8(),
+                            char_offset: self.position.char_offset + 1,
+                        },
+                        Advance::Nothing => Position {
+                            offset: self.position.offset + c.len_utf8(),
+                            char_offset: self.position.char_offset + 1,
+                            ..self.position
+                        },
                     },
+                    tracking: self.tracking,
                 },
             )
         })
     }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl MeasuredInput for PositionedString<'_> {
+    fn measure(&self) -> usize {
+        self.content.len()
+    }
+}
+
+/// Inputs that are a view into a `&str`
+pub trait StrInput<'s>: Input<Part = char> + Sized {
+    fn as_str(&self) -> &'s str;
+}
+
+impl<'s> StrInput<'s> for &'s str {
+    fn as_str(&self) -> &'s str {
+        self
+    }
+}
+
+impl<'s> StrInput<'s> for PositionedString<'s> {
+    fn as_str(&self) -> &'s str {
+        self.content
+    }
+}
+
+/// Inputs that can be cut to the part that was consumed to get from them to their `rest`
+pub trait SliceInput: Input + Sized {
+    type Slice;
+
+    /// `rest` must have been produced by taking parts from `self`
+    fn slice_to(&self, rest: &Self) -> Self::Slice;
+}
+
+impl<'s> SliceInput for &'s str {
+    type Slice = &'s str;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        &self[..self.len() - rest.len()]
+    }
+}
+
+impl<'s> SliceInput for PositionedString<'s> {
+    type Slice = PositionedString<'s>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        Self {
+            content: self.content.slice_to(&rest.content),
+            ..*self
+        }
+    }
+}
+
+impl<'a, T: Clone> SliceInput for &'a [T] {
+    type Slice = &'a [T];
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        &self[..self.len() - rest.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionedSlice<'a, T> {
+    pub content: &'a [T],
+    /// Index of the first element of `content` in the original slice
+    pub index: usize,
+}
+
+impl<'a, T> From<&'a [T]> for PositionedSlice<'a, T> {
+    fn from(content: &'a [T]) -> Self {
+        Self { content, index: 0 }
+    }
+}
+
+impl<'a, T: Clone> Input for PositionedSlice<'a, T> {
+    type Part = T;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        self.content.split_first().map(|(part, rest)| {
+            (
+                part.clone(),
+                Self {
+                    content: rest,
+                    index: self.index + 1,
+                },
+            )
+        })
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl<T: Clone> MeasuredInput for PositionedSlice<'_, T> {
+    fn measure(&self) -> usize {
+        self.content.len()
+    }
+}
+
+impl<'a, T: Clone> SliceInput for PositionedSlice<'a, T> {
+    type Slice = PositionedSlice<'a, T>;
+
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        Self {
+            content: self.content.slice_to(&rest.content),
+            index: self.index,
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Result<T, I, F> {
     /// Parsing completed successfully
     Ok(T, I),
@@ -75,6 +437,7 @@ pub enum Result<T, I, F> {
 }
 
 use crate::Result::{Err, Fatal, Ok};
+use core::ops::{Bound, RangeBounds};
 
 impl<T, I, F> Result<T, I, F> {
     pub fn and<OT, OI>(self, f: impl FnOnce(T, I) -> Result<OT, OI, F>) -> Result<OT, OI, F> {
@@ -100,6 +463,16 @@ impl<T, I, F> Result<T, I, F> {
             Fatal(e) => Fatal(e),
         }
     }
+
+    /// Turns `Err` into `Fatal`. Use it after the parser has committed to the input, so a mistake
+    /// in the input becomes an error instead of making the parser backtrack
+    pub fn cut(self, f: impl FnOnce() -> F) -> Self {
+        match self {
+            Ok(result, rest) => Ok(result, rest),
+            Err => Fatal(f()),
+            Fatal(e) => Fatal(e),
+        }
+    }
 }
 
 pub fn one_part<I: Input, F>(input: I) -> Result<I::Part, I, F> {
@@ -115,7 +488,119 @@ pub fn one_matching_part<I: Input, F>(
     one_part(input).and(|part, rest| if f(&part) { Ok(part, rest) } else { Err })
 }
 
-#[derive(Debug, PartialEq, Eq)]
+pub fn literal<I: Input, P, F>(input: I, parts: impl IntoIterator<Item = P>) -> Result<(), I, F>
+where
+    I::Part: PartialEq<P>,
+{
+    parts
+        .into_iter()
+        .try_fold(input, |rest, expected| {
+            rest.take_one_part()
+                .and_then(|(part, rest)| if part == expected { Some(rest) } else { None })
+        })
+        .map_or(Err, |rest| Ok((), rest))
+}
+
+fn take_bytes<'s, I: StrInput<'s>, F>(input: I, len: usize) -> Result<&'s str, I, F> {
+    let matched = &input.as_str()[..len];
+    let mut rest = input;
+    for _ in matched.chars() {
+        rest = match rest.take_one_part() {
+            Some((_part, rest)) => rest,
+            None => return Err,
+        };
+    }
+    Ok(matched, rest)
+}
+
+pub fn tag<'s, I: StrInput<'s>, F>(input: I, tag: &str) -> Result<&'s str, I, F> {
+    if input.as_str().starts_with(tag) {
+        take_bytes(input, tag.len())
+    } else {
+        Err
+    }
+}
+
+pub fn tag_ignore_ascii_case<'s, I: StrInput<'s>, F>(input: I, tag: &str) -> Result<&'s str, I, F> {
+    match input.as_str().get(..tag.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(tag) => take_bytes(input, tag.len()),
+        _ => Err,
+    }
+}
+
+fn skip_while<I: Input>(mut rest: I, mut f: impl FnMut(&I::Part) -> bool) -> (I, usize) {
+    let mut count = 0;
+    while let Some((part, next)) = rest.take_one_part() {
+        if !f(&part) {
+            break;
+        }
+        rest = next;
+        count += 1;
+    }
+    (rest, count)
+}
+
+pub fn take_while<I: SliceInput + Clone, F>(
+    input: I,
+    f: impl FnMut(&I::Part) -> bool,
+) -> Result<I::Slice, I, F> {
+    let (rest, _count) = skip_while(input.clone(), f);
+    Ok(input.slice_to(&rest), rest)
+}
+
+pub fn take_while1<I: SliceInput + Clone, F>(
+    input: I,
+    f: impl FnMut(&I::Part) -> bool,
+) -> Result<I::Slice, I, F> {
+    match skip_while(input.clone(), f) {
+        (_rest, 0) => Err,
+        (rest, _count) => Ok(input.slice_to(&rest), rest),
+    }
+}
+
+pub fn take_till<I: SliceInput + Clone, F>(
+    input: I,
+    mut f: impl FnMut(&I::Part) -> bool,
+) -> Result<I::Slice, I, F> {
+    take_while(input, |part| !f(part))
+}
+
+/// Takes everything before the first occurrence of `parts`, not consuming `parts` themselves
+pub fn take_until<I: SliceInput + Clone, P, F>(
+    input: I,
+    parts: impl IntoIterator<Item = P> + Clone,
+) -> Result<I::Slice, I, F>
+where
+    I::Part: PartialEq<P>,
+{
+    let mut rest = input.clone();
+    loop {
+        if matches!(literal::<_, _, ()>(rest.clone(), parts.clone()), Ok(..)) {
+            return Ok(input.slice_to(&rest), rest);
+        }
+        rest = match rest.take_one_part() {
+            Some((_part, rest)) => rest,
+            None => return Err,
+        };
+    }
+}
+
+/// Runs `parser` and also returns the span and the text it consumed
+pub fn spanned<'s, T, F>(
+    input: PositionedString<'s>,
+    parser: impl FnOnce(PositionedString<'s>) -> Result<T, PositionedString<'s>, F>,
+) -> Result<(T, Span, &'s str), PositionedString<'s>, F> {
+    parser(input).and(|output, rest| {
+        let span = Span {
+            start: input.position,
+            end: rest.position,
+            byte_range: input.position.offset..rest.position.offset,
+        };
+        Ok((output, span, input.content.slice_to(&rest.content)), rest)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CollResult<T, I, F> {
     Ok(T, I),
     Fatal(F),
@@ -147,45 +632,188 @@ impl<T, I, F> CollResult<T, I, F> {
     }
 }
 
-pub fn collect_repeating<T, I, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
-    mut collection: C,
-    input: I,
+struct Collector<P, I, F> {
     parser: P,
-) -> CollResult<C, I, F> {
-    struct Collector<P, I, F> {
-        parser: P,
-        rest: I,
-        fatal_error: Option<F>,
+    rest: I,
+    fatal_error: Option<F>,
+}
+
+impl<P, I, F> Collector<P, I, F> {
+    fn new(input: I, parser: P) -> Self {
+        Self {
+            fatal_error: None,
+            rest: input,
+            parser,
+        }
+    }
+
+    fn finish<O>(self, output: O) -> CollResult<O, I, F> {
+        match self.fatal_error {
+            None => CollResult::Ok(output, self.rest),
+            Some(err) => CollResult::Fatal(err),
+        }
     }
+}
 
-    impl<T, I, P: FnMut(&I) -> Result<T, I, F>, F> Iterator for Collector<P, I, F> {
-        type Item = T;
+impl<T, I: Input, P: FnMut(&I) -> Result<T, I, F>, F> Iterator for Collector<P, I, F> {
+    type Item = T;
 
-        fn next(&mut self) -> Option<Self::Item> {
-            match (self.parser)(&self.rest) {
-                Err => None,
-                Fatal(err) => {
-                    self.fatal_error = Some(err);
-                    None
-                }
-                Ok(result, rest) => {
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.parser)(&self.rest) {
+            Err => None,
+            Fatal(err) => {
+                self.fatal_error = Some(err);
+                None
+            }
+            Ok(result, rest) => match (self.rest.remaining(), rest.remaining()) {
+                // The parser would output the same thing forever, so it is treated like `Err`
+                (Some(before), Some(after)) if after >= before => None,
+                _ => {
                     self.rest = rest;
                     Some(result)
                 }
-            }
+            },
         }
     }
+}
 
-    let mut collector = Collector {
-        fatal_error: None,
-        rest: input,
-        parser,
-    };
+pub fn collect_repeating<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
+    mut collection: C,
+    input: I,
+    parser: P,
+) -> CollResult<C, I, F> {
+    let mut collector = Collector::new(input, parser);
     collection.extend(&mut collector);
-    match collector.fatal_error {
-        None => CollResult::Ok(collection, collector.rest),
-        Some(err) => CollResult::Fatal(err),
-    }
+    collector.finish(collection)
+}
+
+/// Like `collect_repeating()`, but combines the outputs with `f` instead of collecting them
+pub fn fold_repeating<T, A, I: Input, F, P: FnMut(&I) -> Result<T, I, F>>(
+    init: A,
+    input: I,
+    parser: P,
+    f: impl FnMut(A, T) -> A,
+) -> CollResult<A, I, F> {
+    let mut collector = Collector::new(input, parser);
+    let accumulator = (&mut collector).fold(init, f);
+    collector.finish(accumulator)
+}
+
+/// Like `collect_repeating()`, but throws the outputs away
+pub fn skip_repeating<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>>(
+    input: I,
+    parser: P,
+) -> CollResult<(), I, F> {
+    fold_repeating((), input, parser, |(), _output| ())
+}
+
+/// Like `collect_repeating()`, but stops after the end of `count` and fails with `Err` if there
+/// were fewer items than the start of `count`
+pub fn collect_between<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
+    mut collection: C,
+    input: I,
+    count: impl RangeBounds<usize>,
+    parser: P,
+) -> Result<C, I, F> {
+    let min = match count.start_bound() {
+        Bound::Included(min) => *min,
+        Bound::Excluded(min) => min + 1,
+        Bound::Unbounded => 0,
+    };
+    let max = match count.end_bound() {
+        Bound::Included(max) => *max,
+        Bound::Excluded(max) => max.saturating_sub(1),
+        Bound::Unbounded => usize::MAX,
+    };
+    let mut collector = Collector::new(input, parser);
+    let mut collected = 0;
+    collection.extend((&mut collector).take(max).inspect(|_item| collected += 1));
+    collector.finish(collection).norm().and(|collection, rest| {
+        if collected < min {
+            Err
+        } else {
+            Ok(collection, rest)
+        }
+    })
+}
+
+pub fn collect_at_least<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
+    collection: C,
+    input: I,
+    min: usize,
+    parser: P,
+) -> Result<C, I, F> {
+    collect_between(collection, input, min.., parser)
+}
+
+pub fn collect_at_most<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
+    collection: C,
+    input: I,
+    max: usize,
+    parser: P,
+) -> Result<C, I, F> {
+    collect_between(collection, input, ..=max, parser)
+}
+
+pub fn collect_exactly<T, I: Input, F, P: FnMut(&I) -> Result<T, I, F>, C: Extend<T>>(
+    collection: C,
+    input: I,
+    count: usize,
+    parser: P,
+) -> Result<C, I, F> {
+    collect_between(collection, input, count..=count, parser)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Separated {
+    /// Consume a separator after the last item if there is one
+    pub trailing: bool,
+    /// Fail with `Err` if there are no items
+    pub at_least_one: bool,
+}
+
+pub fn collect_separated<T, S, I: Input, F, C: Extend<T>>(
+    mut collection: C,
+    input: I,
+    options: Separated,
+    mut item: impl FnMut(&I) -> Result<T, I, F>,
+    mut separator: impl FnMut(&I) -> Result<S, I, F>,
+) -> Result<C, I, F> {
+    let rest = match item(&input) {
+        Ok(first, rest) => {
+            collection.extend(Some(first));
+            rest
+        }
+        Err if options.at_least_one => return Err,
+        Err => return Ok(collection, input),
+        Fatal(err) => return Fatal(err),
+    };
+    collect_repeating(collection, rest, |rest| {
+        separator(rest).and(|_separator, rest| item(&rest))
+    })
+    .norm()
+    .and(|collection, rest| {
+        if !options.trailing {
+            return Ok(collection, rest);
+        }
+        match separator(&rest) {
+            Ok(_separator, rest) => Ok(collection, rest),
+            Err => Ok(collection, rest),
+            Fatal(err) => Fatal(err),
+        }
+    })
+}
+
+/// Runs `open`, `inner` and `close` one after another, outputs only the output of `inner`
+pub fn delimited<O, T, C, I, F>(
+    input: I,
+    open: impl FnOnce(I) -> Result<O, I, F>,
+    inner: impl FnOnce(I) -> Result<T, I, F>,
+    close: impl FnOnce(I) -> Result<C, I, F>,
+) -> Result<T, I, F> {
+    open(input)
+        .and(|_open, rest| inner(rest))
+        .and(|output, rest| close(rest).map(|_close| output))
 }
 
 #[cfg(test)]
@@ -211,6 +839,144 @@ mod tests {
         assert_eq!(one_matching_part::<_, ()>("", |_c| true), Err);
     }
 
+    #[test]
+    fn test_matching_literals() {
+        assert_eq!(literal::<_, _, ()>("let x", "let".chars()), Ok((), " x"));
+
+        assert_eq!(literal::<_, _, ()>("lex", "let".chars()), Err);
+
+        assert_eq!(literal::<_, _, ()>("le", "let".chars()), Err);
+
+        assert_eq!(
+            literal::<_, _, ()>(&[1, 2, 3][..], [1, 2]),
+            Ok((), &[3][..])
+        );
+    }
+
+    #[test]
+    fn test_matching_tags() {
+        assert_eq!(tag::<_, ()>("let x", "let"), Ok("let", " x"));
+
+        assert_eq!(tag::<_, ()>("LET x", "let"), Err);
+
+        assert_eq!(tag::<_, ()>("le", "let"), Err);
+
+        assert_eq!(
+            tag::<_, ()>(PositionedString::from("привет\nмир"), "привет\n"),
+            Ok(
+                "привет\n",
+                PositionedString {
+                    position: Position {
+                        row: 2,
+                        column: 1,
+                        offset: 13,
+                        char_offset: 7
+                    },
+                    content: "мир",
+                    tracking: Tracking::default(),
+                }
+            )
+        );
+
+        assert_eq!(
+            tag_ignore_ascii_case::<_, ()>("LeT x", "let"),
+            Ok("LeT", " x")
+        );
+
+        assert_eq!(tag_ignore_ascii_case::<_, ()>("lét", "let"), Err);
+
+        assert_eq!(tag_ignore_ascii_case::<_, ()>("l", "let"), Err);
+    }
+
+    #[test]
+    fn test_taking_while() {
+        assert_eq!(
+            take_while::<_, ()>("123abc", |c| c.is_numeric()),
+            Ok("123", "abc")
+        );
+
+        assert_eq!(
+            take_while::<_, ()>("abc", |c| c.is_numeric()),
+            Ok("", "abc")
+        );
+
+        assert_eq!(take_while::<_, ()>("", |c| c.is_numeric()), Ok("", ""));
+
+        assert_eq!(
+            take_while1::<_, ()>("123abc", |c| c.is_numeric()),
+            Ok("123", "abc")
+        );
+
+        assert_eq!(take_while1::<_, ()>("abc", |c| c.is_numeric()), Err);
+
+        assert_eq!(
+            take_till::<_, ()>("abc;def", |c| *c == ';'),
+            Ok("abc", ";def")
+        );
+
+        assert_eq!(
+            take_while::<_, ()>(&[1, 2, 3, 4][..], |n| *n < 3),
+            Ok(&[1, 2][..], &[3, 4][..])
+        );
+    }
+
+    #[test]
+    fn test_taking_positioned_slices() {
+        let input = PositionedString {
+            content: "ab\ncd;",
+            position: Position {
+                row: 2,
+                column: 3,
+                offset: 10,
+                char_offset: 10,
+            },
+            tracking: Tracking::default(),
+        };
+
+        assert_eq!(
+            take_till::<_, ()>(input, |c| *c == ';'),
+            Ok(
+                PositionedString {
+                    content: "ab\ncd",
+                    position: Position {
+                        row: 2,
+                        column: 3,
+                        offset: 10,
+                        char_offset: 10
+                    },
+                    tracking: Tracking::default(),
+                },
+                PositionedString {
+                    content: ";",
+                    position: Position {
+                        row: 3,
+                        column: 3,
+                        offset: 15,
+                        char_offset: 15
+                    },
+                    tracking: Tracking::default(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_taking_until() {
+        assert_eq!(
+            take_until::<_, _, ()>("a * b */ c", "*/".chars()),
+            Ok("a * b ", "*/ c")
+        );
+
+        assert_eq!(take_until::<_, _, ()>("*/", "*/".chars()), Ok("", "*/"));
+
+        assert_eq!(take_until::<_, _, ()>("a * b", "*/".chars()), Err);
+
+        assert_eq!(
+            take_until::<_, _, ()>(&b"key=value"[..], *b"="),
+            Ok(&b"key"[..], &b"=value"[..])
+        );
+    }
+
     #[test]
     fn test_collecting() {
         let result = collect_repeating(Vec::new(), "123abc", |input| {
@@ -236,6 +1002,189 @@ mod tests {
         assert_eq!(result, CollResult::Fatal(()));
     }
 
+    #[test]
+    fn test_collecting_with_non_consuming_parser() {
+        let result = collect_repeating(Vec::new(), "abc", |input| {
+            take_while::<_, ()>(*input, |c| c.is_numeric())
+        });
+
+        assert_eq!(result, CollResult::Ok(vec![], "abc"));
+
+        let result = collect_repeating(Vec::new(), "12;3", |input| {
+            take_while::<_, ()>(*input, |c| c.is_numeric()).and(|digits, rest| {
+                tag(rest, ";")
+                    .map(|_semicolon| digits)
+                    .or(|| Ok(digits, rest))
+            })
+        });
+
+        assert_eq!(result, CollResult::Ok(vec!["12", "3"], ""));
+
+        assert_eq!(
+            skip_repeating(PositionedString::from("x"), |input| Ok::<_, _, ()>(
+                (),
+                *input
+            )),
+            CollResult::Ok((), PositionedString::from("x"))
+        );
+
+        assert_eq!(
+            collect_at_least(Vec::new(), &[1][..], 1, |input| Ok::<_, _, ()>(0, *input)),
+            Err
+        );
+    }
+
+    fn digit<'s>(input: &&'s str) -> Result<char, &'s str, ()> {
+        one_matching_part(*input, |c| c.is_ascii_digit())
+    }
+
+    #[test]
+    fn test_folding() {
+        let add_digit = |number: u32, digit: char| number * 10 + digit.to_digit(10).unwrap();
+
+        assert_eq!(
+            fold_repeating(0, "123abc", digit, add_digit),
+            CollResult::Ok(123, "abc")
+        );
+
+        assert_eq!(
+            fold_repeating(0, "abc", digit, add_digit),
+            CollResult::Ok(0, "abc")
+        );
+
+        assert_eq!(
+            skip_repeating("   x", |input| tag::<_, ()>(*input, " ")),
+            CollResult::Ok((), "x")
+        );
+
+        assert_eq!(
+            skip_repeating::<(), _, _, _>("", |_input| Fatal(())),
+            CollResult::Fatal(())
+        );
+    }
+
+    #[test]
+    fn test_collecting_bounded() {
+        assert_eq!(
+            collect_exactly(String::new(), "120034", 4, digit),
+            Ok(String::from("1200"), "34")
+        );
+
+        assert_eq!(collect_exactly(String::new(), "120", 4, digit), Err);
+
+        assert_eq!(
+            collect_at_least(String::new(), "12a", 1, digit),
+            Ok(String::from("12"), "a")
+        );
+
+        assert_eq!(collect_at_least(String::new(), "xyz", 1, digit), Err);
+
+        assert_eq!(
+            collect_at_most(String::new(), "123", 2, digit),
+            Ok(String::from("12"), "3")
+        );
+
+        assert_eq!(
+            collect_at_most(String::new(), "x", 2, digit),
+            Ok(String::new(), "x")
+        );
+
+        assert_eq!(
+            collect_between(String::new(), "12345", 2..4, digit),
+            Ok(String::from("123"), "45")
+        );
+
+        let mut calls = 0;
+
+        assert_eq!(
+            collect_at_most(Vec::new(), "123", 2, |input| {
+                calls += 1;
+                digit(input)
+            }),
+            Ok(vec!['1', '2'], "3")
+        );
+
+        assert_eq!(calls, 2);
+
+        assert_eq!(
+            collect_at_least::<(), _, _, _, Vec<_>>(Vec::new(), "", 1, |_input| Fatal(())),
+            Fatal(())
+        );
+    }
+
+    #[test]
+    fn test_collecting_separated() {
+        fn comma<'s>(input: &&'s str) -> Result<&'s str, &'s str, ()> {
+            tag(*input, ",")
+        }
+
+        let options = Separated::default();
+
+        assert_eq!(
+            collect_separated(Vec::new(), "1,2,3)", options, digit, comma),
+            Ok(vec!['1', '2', '3'], ")")
+        );
+
+        assert_eq!(
+            collect_separated(Vec::new(), "1,2,)", options, digit, comma),
+            Ok(vec!['1', '2'], ",)")
+        );
+
+        assert_eq!(
+            collect_separated(Vec::new(), ")", options, digit, comma),
+            Ok(vec![], ")")
+        );
+
+        let options = Separated {
+            trailing: true,
+            at_least_one: true,
+        };
+
+        assert_eq!(
+            collect_separated(Vec::new(), "1,2,)", options, digit, comma),
+            Ok(vec!['1', '2'], ")")
+        );
+
+        assert_eq!(
+            collect_separated(Vec::new(), "1,2)", options, digit, comma),
+            Ok(vec!['1', '2'], ")")
+        );
+
+        assert_eq!(
+            collect_separated(Vec::new(), ",)", options, digit, comma),
+            Err
+        );
+
+        assert_eq!(
+            collect_separated(
+                Vec::new(),
+                "1,2",
+                options,
+                digit,
+                |_input| Fatal::<(), _, _>(())
+            ),
+            Fatal(())
+        );
+    }
+
+    #[test]
+    fn test_delimited_parsing() {
+        let parse = |input: &'static str| {
+            delimited(
+                input,
+                |input| tag::<_, ()>(input, "("),
+                |input| take_while(input, |c: &char| c.is_numeric()),
+                |input| tag(input, ")"),
+            )
+        };
+
+        assert_eq!(parse("(123) rest"), Ok("123", " rest"));
+
+        assert_eq!(parse("(123"), Err);
+
+        assert_eq!(parse("123)"), Err);
+    }
+
     #[test]
     fn test_sequential_parsing() {
         let input = "12345";
@@ -298,6 +1247,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cutting() {
+        #[derive(Debug, PartialEq, Eq)]
+        enum Error {
+            UnclosedString,
+        }
+
+        fn string(input: &str) -> Result<&str, &str, Error> {
+            tag(input, "\"").and(|_quote, rest| {
+                take_till(rest, |c| *c == '"').and(|content, rest| {
+                    tag(rest, "\"")
+                        .map(|_quote| content)
+                        .cut(|| Error::UnclosedString)
+                })
+            })
+        }
+
+        assert_eq!(string("\"abc\" rest"), Ok("abc", " rest"));
+
+        assert_eq!(string("abc"), Err);
+
+        assert_eq!(
+            string("\"abc").or(|| Ok("fallback", "")),
+            Fatal(Error::UnclosedString)
+        );
+    }
+
     #[test]
     fn test_output_mapping() {
         assert_eq!(
@@ -312,7 +1288,12 @@ mod tests {
     fn test_position_tracking() {
         assert_eq!(
             PositionedString::from("").position,
-            Position { row: 1, column: 1 }
+            Position {
+                row: 1,
+                column: 1,
+                offset: 0,
+                char_offset: 0
+            }
         );
 
         assert_eq!(
@@ -320,8 +1301,14 @@ mod tests {
             Ok(
                 '1',
                 PositionedString {
-                    position: Position { row: 1, column: 2 },
-                    content: ""
+                    position: Position {
+                        row: 1,
+                        column: 2,
+                        offset: 1,
+                        char_offset: 1
+                    },
+                    content: "",
+                    tracking: Tracking::default(),
                 }
             )
         );
@@ -331,8 +1318,208 @@ mod tests {
             Ok(
                 '\n',
                 PositionedString {
-                    position: Position { row: 2, column: 1 },
-                    content: ""
+                    position: Position {
+                        row: 2,
+                        column: 1,
+                        offset: 2,
+                        char_offset: 2
+                    },
+                    content: "",
+                    tracking: Tracking::default(),
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_offset_tracking() {
+        let source = "aé\nb";
+
+        let result = collect_repeating(Vec::new(), PositionedString::from(source), |rest| {
+            one_matching_part::<_, ()>(*rest, |c| *c != 'b')
+        });
+
+        let CollResult::Ok(_parts, rest) = result else {
+            panic!("collecting cannot fail here");
+        };
+
+        assert_eq!(
+            rest.position,
+            Position {
+                row: 2,
+                column: 1,
+                offset: 4,
+                char_offset: 3
+            }
+        );
+
+        assert_eq!(&source[rest.position.offset..], "b");
+    }
+
+    #[test]
+    fn test_column_counting() {
+        fn column_after(content: &str, columns: Columns) -> usize {
+            let input = PositionedString::from(content).with_columns(columns);
+            match take_while::<_, ()>(input, |_c| true) {
+                Ok(_taken, rest) => rest.position.column,
+                _ => unreachable!(),
+            }
+        }
+
+        assert_eq!(column_after("aé😀", Columns::Chars), 4);
+
+        assert_eq!(column_after("aé😀", Columns::Utf8), 8);
+
+        assert_eq!(column_after("aé😀", Columns::Utf16), 5);
+
+        assert_eq!(column_after("a\tb", Columns::Visual { tab_width: 4 }), 6);
+
+        assert_eq!(column_after("abcd\t", Columns::Visual { tab_width: 4 }), 9);
+
+        assert_eq!(
+            column_after("世界e\u{301}", Columns::Visual { tab_width: 4 }),
+            6
+        );
+
+        assert_eq!(column_after("ab\nc", Columns::Utf8), 2);
+    }
+
+    #[test]
+    fn test_newline_recognition() {
+        fn row_and_column_after(content: &str, newlines: Newlines) -> (usize, usize) {
+            let input = PositionedString::from(content).with_newlines(newlines);
+            match take_while::<_, ()>(input, |_c| true) {
+                Ok(_taken, rest) => (rest.position.row, rest.position.column),
+                _ => unreachable!(),
+            }
+        }
+
+        assert_eq!(row_and_column_after("a\r\nb", Newlines::Lf), (2, 2));
+
+        assert_eq!(row_and_column_after("a\rb", Newlines::Lf), (1, 4));
+
+        assert_eq!(row_and_column_after("a\r\nb", Newlines::Ascii), (2, 2));
+
+        assert_eq!(row_and_column_after("a\r", Newlines::Ascii), (2, 1));
+
+        assert_eq!(row_and_column_after("a\r\r\nb\n", Newlines::Ascii), (4, 1));
+
+        assert_eq!(row_and_column_after("a\u{2028}b", Newlines::Ascii), (1, 4));
+
+        assert_eq!(
+            row_and_column_after("a\u{2028}b\u{85}c\r\nd", Newlines::Unicode),
+            (4, 2)
+        );
+
+        let input = PositionedString::from("\r\n").with_newlines(Newlines::Ascii);
+
+        assert_eq!(
+            one_part::<_, ()>(input).map(|_c| ()),
+            Ok(
+                (),
+                PositionedString {
+                    content: "\n",
+                    position: Position {
+                        row: 1,
+                        column: 1,
+                        offset: 1,
+                        char_offset: 1,
+                    },
+                    tracking: Tracking {
+                        columns: Columns::Chars,
+                        newlines: Newlines::Ascii,
+                    },
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let input = PositionedString::from("fn\nmain()");
+
+        assert_eq!(
+            tag::<_, ()>(input, "fn\n")
+                .and(|_keyword, rest| spanned(rest, |rest| tag(rest, "main"))),
+            Ok(
+                (
+                    "main",
+                    Span {
+                        start: Position {
+                            row: 2,
+                            column: 1,
+                            offset: 3,
+                            char_offset: 3
+                        },
+                        end: Position {
+                            row: 2,
+                            column: 5,
+                            offset: 7,
+                            char_offset: 7
+                        },
+                        byte_range: 3..7
+                    },
+                    "main"
+                ),
+                PositionedString {
+                    content: "()",
+                    position: Position {
+                        row: 2,
+                        column: 5,
+                        offset: 7,
+                        char_offset: 7
+                    },
+                    tracking: Tracking::default(),
+                }
+            )
+        );
+
+        assert_eq!(spanned::<_, ()>(input, |rest| tag(rest, "main")), Err);
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Token {
+        Number(u32),
+        Plus,
+    }
+
+    #[test]
+    fn test_token_slices() {
+        let tokens = [Token::Number(1), Token::Plus, Token::Number(2)];
+
+        assert_eq!(
+            one_part::<_, ()>(&tokens[..]),
+            Ok(Token::Number(1), &tokens[1..])
+        );
+
+        assert_eq!(
+            one_matching_part::<_, ()>(&tokens[1..], |token| *token == Token::Plus),
+            Ok(Token::Plus, &tokens[2..])
+        );
+
+        assert_eq!(one_part::<&[Token], ()>(&[]), Err);
+
+        let result = collect_repeating(Vec::new(), &tokens[..], |input| {
+            one_matching_part::<_, ()>(*input, |token| matches!(token, Token::Number(_)))
+        });
+
+        assert_eq!(result, CollResult::Ok(vec![Token::Number(1)], &tokens[1..]));
+    }
+
+    #[test]
+    fn test_index_tracking() {
+        let tokens = [Token::Number(1), Token::Plus, Token::Number(2)];
+
+        assert_eq!(PositionedSlice::from(&tokens[..]).index, 0);
+
+        assert_eq!(
+            one_part::<_, ()>(PositionedSlice::from(&tokens[..]))
+                .and(|_token, rest| one_part(rest)),
+            Ok(
+                Token::Plus,
+                PositionedSlice {
+                    content: &tokens[2..],
+                    index: 2
                 }
             )
         );
@@ -0,0 +1,258 @@
+use crate::{Input, MeasuredInput, Result};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A packrat memoization table: remembers the result of every rule at every position, so each
+/// rule is run only once per position no matter how much the grammar backtracks.
+///
+/// Positions are told apart by `Input::remaining()`, so a table must only be used with one
+/// source; inputs that cannot measure what is remaining are never memoized
+pub struct Memo<K, T, I, F> {
+    table: HashMap<(K, usize), Result<T, I, F>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K, T, I, F> Default for Memo<K, T, I, F> {
+    fn default() -> Self {
+        Self {
+            table: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq, T: Clone, I: Input + Clone, F: Clone> Memo<K, T, I, F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `parser`, or returns what it returned the previous time `rule` was run at the same
+    /// position. `parser` gets the table too, so it can run memoized rules inside of it
+    pub fn run(
+        &mut self,
+        rule: K,
+        input: I,
+        parser: impl FnOnce(&mut Self, I) -> Result<T, I, F>,
+    ) -> Result<T, I, F> {
+        let Some(position) = input.remaining() else {
+            self.misses += 1;
+            return parser(self, input);
+        };
+        let key = (rule, position);
+        if let Some(result) = self.table.get(&key) {
+            self.hits += 1;
+            return result.clone();
+        }
+        self.misses += 1;
+        let result = parser(self, input);
+        self.table.insert(key, result.clone());
+        result
+    }
+}
+
+impl<K: Hash + Eq + Clone, T: Clone, I: MeasuredInput + Clone, F: Clone> Memo<K, T, I, F> {
+    /// Like `run()`, but for a directly left-recursive rule (like `expression = expression "-"
+    /// number | number`): the recursive call at the same position first fails, then gets the
+    /// previous result of the rule, and so on while the result keeps consuming more of the input.
+    ///
+    /// Only rules that call themselves directly are supported, other rules that are memoized at
+    /// the same position while the result is growing may remember outdated results. The input
+    /// has to be a `MeasuredInput`, since the recursive call cannot be detected otherwise
+    pub fn run_left_recursive(
+        &mut self,
+        rule: K,
+        input: I,
+        mut parser: impl FnMut(&mut Self, I) -> Result<T, I, F>,
+    ) -> Result<T, I, F> {
+        let key = (rule, input.measure());
+        if let Some(result) = self.table.get(&key) {
+            self.hits += 1;
+            return result.clone();
+        }
+        self.misses += 1;
+        let mut best = Result::Err;
+        let mut best_remaining = None;
+        loop {
+            self.table.insert(key.clone(), best.clone());
+            let result = parser(self, input.clone());
+            match &result {
+                Result::Ok(_result, rest) => {
+                    let remaining = rest.measure();
+                    if best_remaining.is_some_and(|best| remaining >= best) {
+                        break;
+                    }
+                    best_remaining = Some(remaining);
+                    best = result;
+                }
+                Result::Err => break,
+                Result::Fatal(_error) => {
+                    best = result;
+                    break;
+                }
+            }
+        }
+        self.table.insert(key, best.clone());
+        best
+    }
+}
+
+impl<K, T, I, F> Memo<K, T, I, F> {
+    /// How many times a stored result was returned
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// How many times a parser had to be run
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Forgets all the results (but not the statistics), for example, to reuse the table with
+    /// another source
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{one_matching_part, tag, PositionedString};
+
+    type Rule = &'static str;
+    type Table<'s> = Memo<Rule, u32, PositionedString<'s>, ()>;
+
+    fn number<'s>(
+        memo: &mut Table<'s>,
+        input: PositionedString<'s>,
+    ) -> Result<u32, PositionedString<'s>, ()> {
+        memo.run("number", input, |_memo, input| {
+            one_matching_part(input, |c| c.is_ascii_digit())
+                .map(|digit| digit.to_digit(10).unwrap())
+        })
+    }
+
+    /// `number "+" number | number "-" number | number`, which parses the first number three times
+    /// without memoization
+    fn expression<'s>(
+        memo: &mut Table<'s>,
+        input: PositionedString<'s>,
+    ) -> Result<u32, PositionedString<'s>, ()> {
+        memo.run("expression", input, |memo, input| {
+            number(memo, input)
+                .and(|left, rest| {
+                    tag(rest, "+").and(|_plus, rest| number(memo, rest).map(|right| left + right))
+                })
+                .or(|| {
+                    number(memo, input).and(|left, rest| {
+                        tag(rest, "-")
+                            .and(|_minus, rest| number(memo, rest).map(|right| left - right))
+                    })
+                })
+                .or(|| number(memo, input))
+        })
+    }
+
+    #[test]
+    fn test_memoizing() {
+        let mut memo = Memo::new();
+        let input = PositionedString::from("7-2");
+
+        assert!(matches!(
+            expression(&mut memo, input),
+            crate::Result::Ok(5, _)
+        ));
+
+        // "expression" at 0, "number" at 0 and "number" at 2
+        assert_eq!(memo.misses(), 3);
+
+        // "number" at 0 in the second alternative
+        assert_eq!(memo.hits(), 1);
+
+        assert!(matches!(
+            expression(&mut memo, input),
+            crate::Result::Ok(5, _)
+        ));
+
+        assert_eq!(memo.hits(), 2);
+
+        memo.clear();
+
+        assert!(matches!(
+            expression(&mut memo, PositionedString::from("1")),
+            crate::Result::Ok(1, _)
+        ));
+
+        assert_eq!(memo.misses(), 5);
+
+        assert_eq!(memo.hits(), 4);
+    }
+
+    /// `subtraction = subtraction "-" number | number`
+    fn subtraction<'s>(
+        memo: &mut Memo<Rule, i32, PositionedString<'s>, ()>,
+        input: PositionedString<'s>,
+    ) -> Result<i32, PositionedString<'s>, ()> {
+        memo.run_left_recursive("subtraction", input, |memo, input| {
+            subtraction(memo, input)
+                .and(|left, rest| {
+                    tag(rest, "-").and(|_minus, rest| {
+                        one_matching_part(rest, |c| c.is_ascii_digit())
+                            .map(|digit| left - digit.to_digit(10).unwrap() as i32)
+                    })
+                })
+                .or(|| {
+                    one_matching_part(input, |c| c.is_ascii_digit())
+                        .map(|digit| digit.to_digit(10).unwrap() as i32)
+                })
+        })
+    }
+
+    #[test]
+    fn test_left_recursion() {
+        let mut memo = Memo::new();
+
+        let crate::Result::Ok(result, rest) =
+            subtraction(&mut memo, PositionedString::from("9-5-3!"))
+        else {
+            panic!("the input starts with a subtraction");
+        };
+
+        assert_eq!(result, 1);
+
+        assert_eq!(rest.content, "!");
+
+        let mut memo = Memo::new();
+
+        assert!(matches!(
+            subtraction(&mut memo, PositionedString::from("7")),
+            crate::Result::Ok(7, _)
+        ));
+
+        let mut memo = Memo::new();
+
+        assert_eq!(
+            subtraction(&mut memo, PositionedString::from("-1")),
+            crate::Result::Err
+        );
+    }
+
+    #[test]
+    fn test_memoizing_failures() {
+        let mut memo = Memo::<Rule, (), &[u8], ()>::new();
+        let mut runs = 0;
+
+        for _ in 0..3 {
+            let result = memo.run("fatal", &[1, 2][..], |_memo, _input| {
+                runs += 1;
+                crate::Result::Fatal(())
+            });
+
+            assert_eq!(result, crate::Result::Fatal(()));
+        }
+
+        assert_eq!(runs, 1);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{
+    collect_repeating, Input,
+    Result::{self, Err, Fatal, Ok},
+};
+
+/// A parser as a value. Every `FnMut(I) -> parco::Result<T, I, F>` is a parser, so plain parser
+/// functions can be combined with the methods of this trait into bigger parsers that can be stored
+/// and passed around
+pub trait Parser<I> {
+    type Output;
+    type Fatal;
+
+    fn parse(&mut self, input: I) -> Result<Self::Output, I, Self::Fatal>;
+
+    fn map<O, M: FnMut(Self::Output) -> O>(self, f: M) -> Map<Self, M>
+    where
+        Self: Sized,
+    {
+        Map { parser: self, f }
+    }
+
+    /// Runs `next` on the rest of the input, outputs both outputs
+    fn then<P: Parser<I, Fatal = Self::Fatal>>(self, next: P) -> Then<Self, P>
+    where
+        Self: Sized,
+    {
+        Then {
+            first: self,
+            second: next,
+        }
+    }
+
+    /// Runs `other` on the same input if this parser fails with `Err`
+    fn or<P: Parser<I, Output = Self::Output, Fatal = Self::Fatal>>(self, other: P) -> Or<Self, P>
+    where
+        Self: Sized,
+    {
+        Or {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Repeats this parser with `collect_repeating`, outputs a `Vec`
+    fn many(self) -> Many<Self>
+    where
+        Self: Sized,
+    {
+        Many { parser: self }
+    }
+
+    /// Outputs `None` instead of failing with `Err`
+    fn optional(self) -> Optional<Self>
+    where
+        Self: Sized,
+    {
+        Optional { parser: self }
+    }
+
+    /// Turns `Err` into `Fatal(f())`, see `parco::Result::cut()`
+    fn cut<C: FnMut() -> Self::Fatal>(self, f: C) -> Cut<Self, C>
+    where
+        Self: Sized,
+    {
+        Cut { parser: self, f }
+    }
+}
+
+impl<I, T, F, P: FnMut(I) -> Result<T, I, F>> Parser<I> for P {
+    type Output = T;
+    type Fatal = F;
+
+    fn parse(&mut self, input: I) -> Result<T, I, F> {
+        self(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Map<P, M> {
+    parser: P,
+    f: M,
+}
+
+impl<I, O, P: Parser<I>, M: FnMut(P::Output) -> O> Parser<I> for Map<P, M> {
+    type Output = O;
+    type Fatal = P::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<O, I, P::Fatal> {
+        self.parser.parse(input).map(&mut self.f)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<I, A: Parser<I>, B: Parser<I, Fatal = A::Fatal>> Parser<I> for Then<A, B> {
+    type Output = (A::Output, B::Output);
+    type Fatal = A::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<Self::Output, I, A::Fatal> {
+        let second = &mut self.second;
+        self.first
+            .parse(input)
+            .and(|first, rest| second.parse(rest).map(|second| (first, second)))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Or<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<I: Clone, A: Parser<I>, B: Parser<I, Output = A::Output, Fatal = A::Fatal>> Parser<I>
+    for Or<A, B>
+{
+    type Output = A::Output;
+    type Fatal = A::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<A::Output, I, A::Fatal> {
+        let second = &mut self.second;
+        self.first.parse(input.clone()).or(|| second.parse(input))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Many<P> {
+    parser: P,
+}
+
+impl<I: Input + Clone, P: Parser<I>> Parser<I> for Many<P> {
+    type Output = Vec<P::Output>;
+    type Fatal = P::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<Self::Output, I, P::Fatal> {
+        collect_repeating(Vec::new(), input, |rest: &I| {
+            self.parser.parse(rest.clone())
+        })
+        .norm()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Optional<P> {
+    parser: P,
+}
+
+impl<I: Clone, P: Parser<I>> Parser<I> for Optional<P> {
+    type Output = Option<P::Output>;
+    type Fatal = P::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<Self::Output, I, P::Fatal> {
+        match self.parser.parse(input.clone()) {
+            Ok(result, rest) => Ok(Some(result), rest),
+            Err => Ok(None, input),
+            Fatal(e) => Fatal(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cut<P, C> {
+    parser: P,
+    f: C,
+}
+
+impl<I, P: Parser<I>, C: FnMut() -> P::Fatal> Parser<I> for Cut<P, C> {
+    type Output = P::Output;
+    type Fatal = P::Fatal;
+
+    fn parse(&mut self, input: I) -> Result<P::Output, I, P::Fatal> {
+        self.parser.parse(input).cut(&mut self.f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{one_matching_part, tag};
+
+    fn digit(input: &str) -> Result<char, &str, ()> {
+        one_matching_part(input, |c| c.is_ascii_digit())
+    }
+
+    fn minus(input: &str) -> Result<&str, &str, ()> {
+        tag(input, "-")
+    }
+
+    fn number<'s>() -> impl Parser<&'s str, Output = i64, Fatal = ()> {
+        minus
+            .optional()
+            .then(digit.many())
+            .map(|(minus, digits): (Option<&str>, Vec<char>)| {
+                let number: i64 = digits.into_iter().collect::<String>().parse().unwrap_or(0);
+                if minus.is_some() {
+                    -number
+                } else {
+                    number
+                }
+            })
+    }
+
+    #[test]
+    fn test_combining_parsers() {
+        let mut number = number();
+
+        assert_eq!(number.parse("123abc"), Ok(123, "abc"));
+
+        assert_eq!(number.parse("-45"), Ok(-45, ""));
+
+        assert_eq!(number.parse("abc"), Ok(0, "abc"));
+
+        assert_eq!(digit.then(digit).parse("12"), Ok(('1', '2'), ""));
+
+        assert_eq!(digit.then(digit).parse("1a"), Err);
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let mut sign = minus.or(|input| tag(input, "+"));
+
+        assert_eq!(sign.parse("+1"), Ok("+", "1"));
+
+        assert_eq!(sign.parse("-1"), Ok("-", "1"));
+
+        assert_eq!(sign.parse("1"), Err);
+
+        let mut fatal = (|_input: &str| Fatal::<char, &str, _>(())).or(digit);
+
+        assert_eq!(fatal.parse("1"), Fatal(()));
+    }
+
+    #[test]
+    fn test_cutting() {
+        let mut negative = minus.then(digit.cut(|| ()));
+
+        assert_eq!(negative.parse("-1"), Ok(("-", '1'), ""));
+
+        assert_eq!(negative.parse("1"), Err);
+
+        assert_eq!(negative.parse("-a"), Fatal(()));
+    }
+
+    #[test]
+    fn test_storing_parsers() {
+        let mut parsers: Vec<Box<dyn Parser<&str, Output = char, Fatal = ()>>> = vec![
+            Box::new(digit),
+            Box::new(digit.map(|_digit| 'x')),
+            Box::new(minus.map(|_minus| '-')),
+        ];
+
+        let outputs: Vec<_> = parsers.iter_mut().map(|parser| parser.parse("1")).collect();
+
+        assert_eq!(outputs, vec![Ok('1', ""), Ok('x', ""), Err]);
+    }
+}
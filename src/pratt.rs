@@ -0,0 +1,317 @@
+use crate::{
+    Input,
+    Result::{self, Err, Fatal, Ok},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`
+    Right,
+}
+
+type OperatorParser<'p, O, I, F> = Box<dyn Fn(I) -> Result<O, I, F> + 'p>;
+
+struct Prefix<'p, T, O, I, F> {
+    binding_power: u32,
+    parser: OperatorParser<'p, O, I, F>,
+    build: Box<dyn Fn(O, T) -> T + 'p>,
+}
+
+struct Infix<'p, T, O, I, F> {
+    binding_power: u32,
+    associativity: Associativity,
+    parser: OperatorParser<'p, O, I, F>,
+    build: Box<dyn Fn(O, T, T) -> T + 'p>,
+}
+
+struct Postfix<'p, T, O, I, F> {
+    binding_power: u32,
+    parser: OperatorParser<'p, O, I, F>,
+    build: Box<dyn Fn(O, T) -> T + 'p>,
+}
+
+/// A Pratt parser of expressions. Operators are parsed with parco parsers that output `O` (like the
+/// matched operator) and are turned into expression nodes `T` with the given functions. Operators
+/// with bigger binding powers bind tighter; the operators that were registered first are tried
+/// first. Binding powers start from 1, registering an operator with 0 panics. Prefix and postfix
+/// operators that match without consuming anything are ignored
+pub struct Pratt<'p, T, O, I, F> {
+    prefix: Vec<Prefix<'p, T, O, I, F>>,
+    infix: Vec<Infix<'p, T, O, I, F>>,
+    postfix: Vec<Postfix<'p, T, O, I, F>>,
+}
+
+impl<T, O, I, F> Default for Pratt<'_, T, O, I, F> {
+    fn default() -> Self {
+        Self {
+            prefix: Vec::new(),
+            infix: Vec::new(),
+            postfix: Vec::new(),
+        }
+    }
+}
+
+impl<'p, T, O, I: Input + Clone, F> Pratt<'p, T, O, I, F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prefix(
+        mut self,
+        binding_power: u32,
+        parser: impl Fn(I) -> Result<O, I, F> + 'p,
+        build: impl Fn(O, T) -> T + 'p,
+    ) -> Self {
+        assert_binding_power(binding_power);
+        self.prefix.push(Prefix {
+            binding_power,
+            parser: Box::new(parser),
+            build: Box::new(build),
+        });
+        self
+    }
+
+    pub fn infix(
+        mut self,
+        binding_power: u32,
+        associativity: Associativity,
+        parser: impl Fn(I) -> Result<O, I, F> + 'p,
+        build: impl Fn(O, T, T) -> T + 'p,
+    ) -> Self {
+        assert_binding_power(binding_power);
+        self.infix.push(Infix {
+            binding_power,
+            associativity,
+            parser: Box::new(parser),
+            build: Box::new(build),
+        });
+        self
+    }
+
+    pub fn postfix(
+        mut self,
+        binding_power: u32,
+        parser: impl Fn(I) -> Result<O, I, F> + 'p,
+        build: impl Fn(O, T) -> T + 'p,
+    ) -> Self {
+        assert_binding_power(binding_power);
+        self.postfix.push(Postfix {
+            binding_power,
+            parser: Box::new(parser),
+            build: Box::new(build),
+        });
+        self
+    }
+
+    /// Parses an expression made of operators and operands parsed with `operand`. `operand` can
+    /// call `parse()` again, for example, to parse an expression in parentheses
+    pub fn parse(&self, input: I, operand: &dyn Fn(I) -> Result<T, I, F>) -> Result<T, I, F> {
+        self.parse_above(input, 0, operand)
+    }
+
+    /// Parses an expression that only has operators with binding powers above `min_binding_power`
+    fn parse_above(
+        &self,
+        input: I,
+        min_binding_power: u32,
+        operand: &dyn Fn(I) -> Result<T, I, F>,
+    ) -> Result<T, I, F> {
+        let (mut left, mut rest) = match self.parse_prefixed(input, operand) {
+            Ok(left, rest) => (left, rest),
+            Err => return Err,
+            Fatal(error) => return Fatal(error),
+        };
+        'operators: loop {
+            for postfix in &self.postfix {
+                if postfix.binding_power <= min_binding_power {
+                    continue;
+                }
+                match (postfix.parser)(rest.clone()) {
+                    // An operator that consumes nothing would be applied forever
+                    Ok(_operator, after) if !consumed(&rest, &after) => {}
+                    Ok(operator, after) => {
+                        left = (postfix.build)(operator, left);
+                        rest = after;
+                        continue 'operators;
+                    }
+                    Err => {}
+                    Fatal(error) => return Fatal(error),
+                }
+            }
+            for infix in &self.infix {
+                if infix.binding_power <= min_binding_power {
+                    continue;
+                }
+                let operator = match (infix.parser)(rest.clone()) {
+                    Ok(operator, after) => (operator, after),
+                    Err => continue,
+                    Fatal(error) => return Fatal(error),
+                };
+                let right_binding_power = match infix.associativity {
+                    Associativity::Left => infix.binding_power,
+                    Associativity::Right => infix.binding_power.saturating_sub(1),
+                };
+                match self.parse_above(operator.1, right_binding_power, operand) {
+                    Ok(right, after) => {
+                        left = (infix.build)(operator.0, left, right);
+                        rest = after;
+                        continue 'operators;
+                    }
+                    // Without an operand after it, the operator is not a part of the expression
+                    Err => {}
+                    Fatal(error) => return Fatal(error),
+                }
+            }
+            return Ok(left, rest);
+        }
+    }
+
+    fn parse_prefixed(&self, input: I, operand: &dyn Fn(I) -> Result<T, I, F>) -> Result<T, I, F> {
+        for prefix in &self.prefix {
+            match (prefix.parser)(input.clone()) {
+                Ok(_operator, rest) if !consumed(&input, &rest) => {}
+                Ok(operator, rest) => {
+                    match self.parse_above(rest, prefix.binding_power.saturating_sub(1), operand) {
+                        Ok(operand, rest) => return Ok((prefix.build)(operator, operand), rest),
+                        Err => {}
+                        Fatal(error) => return Fatal(error),
+                    }
+                }
+                Err => {}
+                Fatal(error) => return Fatal(error),
+            }
+        }
+        operand(input)
+    }
+}
+
+/// Whether `after` is further into the input than `before`, as far as the input can tell
+fn consumed<I: Input>(before: &I, after: &I) -> bool {
+    !matches!(
+        (before.remaining(), after.remaining()),
+        (Some(before), Some(after)) if after >= before
+    )
+}
+
+fn assert_binding_power(binding_power: u32) {
+    assert!(binding_power > 0, "binding powers start from 1");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{delimited, one_matching_part, tag};
+
+    type Expressions<'p> = Pratt<'p, String, &'static str, &'static str, &'static str>;
+
+    fn operator(
+        symbol: &'static str,
+    ) -> impl Fn(&'static str) -> Result<&'static str, &'static str, &'static str> {
+        move |input| tag(input, symbol)
+    }
+
+    fn expressions<'p>() -> Expressions<'p> {
+        Pratt::new()
+            .infix(1, Associativity::Left, operator("+"), |op, l, r| {
+                format!("({l} {op} {r})")
+            })
+            .infix(1, Associativity::Left, operator("-"), |op, l, r| {
+                format!("({l} {op} {r})")
+            })
+            .infix(2, Associativity::Left, operator("*"), |op, l, r| {
+                format!("({l} {op} {r})")
+            })
+            .infix(4, Associativity::Right, operator("^"), |op, l, r| {
+                format!("({l} {op} {r})")
+            })
+            .prefix(3, operator("-"), |op, e| format!("({op}{e})"))
+            .postfix(5, operator("!"), |op, e| format!("({e}{op})"))
+            .postfix(
+                5,
+                |input| tag(input, "?").and(|_op, _rest| Fatal("`?` is not supported")),
+                |op, e| format!("({e}{op})"),
+            )
+    }
+
+    fn operand(
+        pratt: &Expressions,
+        input: &'static str,
+    ) -> Result<String, &'static str, &'static str> {
+        one_matching_part(input, |c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_string())
+            .or(|| {
+                delimited(
+                    input,
+                    operator("("),
+                    |input| pratt.parse(input, &|input| operand(pratt, input)),
+                    operator(")"),
+                )
+            })
+    }
+
+    fn parse(input: &'static str) -> Result<String, &'static str, &'static str> {
+        let pratt = expressions();
+        pratt.parse(input, &|input| operand(&pratt, input))
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(parse("1+2*3"), Ok("(1 + (2 * 3))".into(), ""));
+
+        assert_eq!(parse("1*2+3"), Ok("((1 * 2) + 3)".into(), ""));
+
+        assert_eq!(parse("(1+2)*3"), Ok("((1 + 2) * 3)".into(), ""));
+    }
+
+    #[test]
+    fn test_associativity() {
+        assert_eq!(parse("1-2-3"), Ok("((1 - 2) - 3)".into(), ""));
+
+        assert_eq!(parse("1^2^3"), Ok("(1 ^ (2 ^ 3))".into(), ""));
+    }
+
+    #[test]
+    fn test_prefix_and_postfix() {
+        assert_eq!(parse("-1*2"), Ok("((-1) * 2)".into(), ""));
+
+        assert_eq!(parse("-1^2"), Ok("(-(1 ^ 2))".into(), ""));
+
+        assert_eq!(parse("-a!"), Ok("(-(a!))".into(), ""));
+
+        assert_eq!(parse("--1-1"), Ok("((-(-1)) - 1)".into(), ""));
+    }
+
+    #[test]
+    fn test_failing() {
+        assert_eq!(parse("+1"), Err);
+
+        assert_eq!(parse("1+"), Ok("1".into(), "+"));
+
+        assert_eq!(parse("1 + 2"), Ok("1".into(), " + 2"));
+
+        assert_eq!(parse("1+2?"), Fatal("`?` is not supported"));
+
+        assert_eq!(parse("(1+2"), Err);
+    }
+
+    #[test]
+    #[should_panic(expected = "binding powers start from 1")]
+    fn test_zero_binding_power() {
+        let _pratt: Expressions =
+            Pratt::new().postfix(0, operator("!"), |op, e| format!("({e}{op})"));
+    }
+
+    #[test]
+    fn test_zero_width_operators() {
+        let pratt = expressions()
+            .prefix(3, |input| tag(input, ""), |_op, e| format!("(+{e})"))
+            .postfix(5, |input| tag(input, ""), |_op, e| format!("({e}+)"));
+
+        assert_eq!(
+            pratt.parse("-1!", &|input| operand(&pratt, input)),
+            Ok("(-(1!))".into(), "")
+        );
+    }
+}
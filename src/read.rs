@@ -0,0 +1,262 @@
+use crate::{Input, MeasuredInput, Result, SliceInput};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    /// The parser went back further than the lookback limit allows
+    LookbackExceeded {
+        offset: usize,
+        /// The offset of the oldest byte that is still kept
+        window_start: usize,
+    },
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "cannot read the input: {error}"),
+            Self::LookbackExceeded {
+                offset,
+                window_start,
+            } => write!(
+                f,
+                "cannot go back to byte {offset}, only bytes starting from {window_start} are kept"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {}
+
+struct Window<R> {
+    reader: R,
+    bytes: VecDeque<u8>,
+    /// The offset of `bytes[0]`
+    start: usize,
+    /// How many bytes before the furthest read byte are kept
+    lookback: usize,
+    furthest: usize,
+    end: bool,
+    error: Option<ReadError>,
+}
+
+impl<R: BufRead> Window<R> {
+    fn get(&mut self, offset: usize) -> Option<u8> {
+        if offset < self.start {
+            self.error.get_or_insert(ReadError::LookbackExceeded {
+                offset,
+                window_start: self.start,
+            });
+            return None;
+        }
+        while offset >= self.start + self.bytes.len() && !self.end && self.error.is_none() {
+            match self.reader.fill_buf() {
+                Ok([]) => self.end = true,
+                Ok(chunk) => {
+                    let length = chunk.len();
+                    self.bytes.extend(chunk);
+                    self.reader.consume(length);
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => self.error = Some(ReadError::Io(error)),
+            }
+        }
+        let byte = *self.bytes.get(offset - self.start)?;
+        self.furthest = self.furthest.max(offset + 1);
+        let keep_from = self.furthest.saturating_sub(self.lookback);
+        if keep_from > self.start {
+            self.bytes.drain(..keep_from - self.start);
+            self.start = keep_from;
+        }
+        Some(byte)
+    }
+}
+
+/// Bytes read from a `BufRead` on demand. Clones share the bytes that were read, and the bytes that
+/// are more than `lookback` bytes behind the furthest read byte are dropped, so backtracking only
+/// works within the lookback limit.
+///
+/// Since taking a part cannot fail, reading errors (including going back too far) end the input
+/// and are recorded; use `checked()` to turn them into `Fatal`
+pub struct ReadInput<R> {
+    window: Rc<RefCell<Window<R>>>,
+    offset: usize,
+}
+
+impl<R> Clone for ReadInput<R> {
+    fn clone(&self) -> Self {
+        Self {
+            window: Rc::clone(&self.window),
+            offset: self.offset,
+        }
+    }
+}
+
+impl<R> fmt::Debug for ReadInput<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadInput")
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: BufRead> ReadInput<R> {
+    pub fn new(reader: R, lookback: usize) -> Self {
+        Self {
+            window: Rc::new(RefCell::new(Window {
+                reader,
+                bytes: VecDeque::new(),
+                start: 0,
+                lookback,
+                furthest: 0,
+                end: false,
+                error: None,
+            })),
+            offset: 0,
+        }
+    }
+
+    /// How many bytes were taken from the beginning of the reader to get to this input
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Takes the error that happened while reading, if there was one
+    pub fn take_error(&self) -> Option<ReadError> {
+        self.window.borrow_mut().error.take()
+    }
+
+    /// Turns `result` into `Fatal` if there was a reading error while getting it
+    pub fn checked<T, F: From<ReadError>>(&self, result: Result<T, Self, F>) -> Result<T, Self, F> {
+        match self.take_error() {
+            Some(error) => Result::Fatal(error.into()),
+            None => result,
+        }
+    }
+}
+
+impl<R: BufRead> Input for ReadInput<R> {
+    type Part = u8;
+
+    fn take_one_part(&self) -> Option<(Self::Part, Self)> {
+        let byte = self.window.borrow_mut().get(self.offset)?;
+        Some((
+            byte,
+            Self {
+                window: Rc::clone(&self.window),
+                offset: self.offset + 1,
+            },
+        ))
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.measure())
+    }
+}
+
+impl<R: BufRead> MeasuredInput for ReadInput<R> {
+    fn measure(&self) -> usize {
+        usize::MAX - self.offset
+    }
+}
+
+impl<R: BufRead> SliceInput for ReadInput<R> {
+    type Slice = Vec<u8>;
+
+    /// Copies the bytes, since they may be dropped from the window later. Slices that are longer
+    /// than the lookback limit cannot be made
+    fn slice_to(&self, rest: &Self) -> Self::Slice {
+        let mut window = self.window.borrow_mut();
+        (self.offset..rest.offset)
+            .map_while(|offset| window.get(offset))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{literal, one_part, take_while, Result::Fatal, Result::Ok};
+    use std::io::BufReader;
+
+    #[derive(Debug)]
+    enum Error {
+        Read(ReadError),
+    }
+
+    impl From<ReadError> for Error {
+        fn from(error: ReadError) -> Self {
+            Self::Read(error)
+        }
+    }
+
+    /// Gives out at most `chunk` bytes at a time
+    fn reader(content: &'static [u8], chunk: usize) -> BufReader<&'static [u8]> {
+        BufReader::with_capacity(chunk, content)
+    }
+
+    #[test]
+    fn test_reading() {
+        let input = ReadInput::new(reader(b"GET /index", 3), 16);
+
+        let result = take_while::<_, Error>(input.clone(), |byte| *byte != b' ');
+        let Ok(method, rest) = input.checked(result) else {
+            panic!("the input is readable");
+        };
+
+        assert_eq!(method, b"GET");
+
+        assert_eq!(rest.offset(), 3);
+
+        assert!(matches!(
+            literal::<_, _, Error>(rest, *b" /index"),
+            Ok((), rest) if rest.take_one_part().is_none()
+        ));
+    }
+
+    #[test]
+    fn test_backtracking() {
+        let input = ReadInput::new(reader(b"abcdefgh", 2), 4);
+
+        let Ok(_taken, rest) = take_while::<_, Error>(input.clone(), |byte| *byte != b'c') else {
+            panic!("the input is readable");
+        };
+
+        assert!(matches!(one_part::<_, Error>(input.clone()), Ok(b'a', _)));
+
+        assert!(matches!(
+            literal::<_, _, Error>(rest, *b"cdefgh"),
+            Ok((), _)
+        ));
+
+        assert!(matches!(
+            input.checked(one_part::<_, Error>(input.clone())),
+            Fatal(Error::Read(ReadError::LookbackExceeded {
+                offset: 0,
+                window_start: 4
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_reading_errors() {
+        struct Failing;
+
+        impl io::Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disconnected"))
+            }
+        }
+
+        let input = ReadInput::new(BufReader::new(Failing), 16);
+
+        let result = input.checked(one_part::<_, Error>(input.clone()));
+
+        assert!(matches!(result, Fatal(Error::Read(ReadError::Io(_)))));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    Input,
+    Result::{self, Err, Fatal, Ok},
+};
+
+/// Everything that was parsed, together with the errors that were recovered from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered<T, I, F> {
+    pub output: T,
+    pub rest: I,
+    pub errors: Vec<F>,
+}
+
+/// Skips parts up to and including the first one that satisfies `sync`, or up to the end
+pub fn skip_past<I: Input>(mut rest: I, mut sync: impl FnMut(&I::Part) -> bool) -> I {
+    while let Some((part, next)) = rest.take_one_part() {
+        rest = next;
+        if sync(&part) {
+            break;
+        }
+    }
+    rest
+}
+
+/// Runs `parser`. If it fails with `Fatal`, records the error in `errors`, skips the input past the
+/// next synchronization point (see `skip_past()`) and outputs `error_node(&error)` instead, so
+/// parsing can go on. `Err` is left as is, since it is not a mistake in the input
+pub fn recover<T, I: Input + Clone, F>(
+    errors: &mut Vec<F>,
+    input: I,
+    parser: impl FnOnce(I) -> Result<T, I, F>,
+    sync: impl FnMut(&I::Part) -> bool,
+    error_node: impl FnOnce(&F) -> T,
+) -> Result<T, I, F> {
+    match parser(input.clone()) {
+        Ok(result, rest) => Ok(result, rest),
+        Err => Err,
+        Fatal(error) => {
+            let node = error_node(&error);
+            errors.push(error);
+            Ok(node, skip_past(input, sync))
+        }
+    }
+}
+
+/// Like `collect_repeating()`, but recovers from every `Fatal` with `recover()`. A `Fatal` at the
+/// end of the input cannot be skipped, so its error node is the last item
+pub fn collect_recovering<T, I: Input + Clone, F, C: Extend<T>>(
+    mut collection: C,
+    input: I,
+    mut parser: impl FnMut(I) -> Result<T, I, F>,
+    mut sync: impl FnMut(&I::Part) -> bool,
+    mut error_node: impl FnMut(&F) -> T,
+) -> Recovered<C, I, F> {
+    let mut errors = Vec::new();
+    let mut rest = input;
+    loop {
+        let errors_before = errors.len();
+        let (item, next) = match recover(
+            &mut errors,
+            rest.clone(),
+            &mut parser,
+            &mut sync,
+            &mut error_node,
+        ) {
+            Ok(item, next) => (item, next),
+            Err => break,
+            Fatal(_error) => unreachable!("`recover()` never fails with `Fatal`"),
+        };
+        if errors.len() > errors_before && rest.take_one_part().is_none() {
+            collection.extend(Some(item));
+            rest = next;
+            break;
+        }
+        // Like in `collect_repeating()`, a parser that does not consume anything stops the loop
+        if let (Some(before), Some(after)) = (rest.remaining(), next.remaining()) {
+            if after >= before {
+                break;
+            }
+        }
+        collection.extend(Some(item));
+        rest = next;
+    }
+    Recovered {
+        output: collection,
+        rest,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tag, take_while1, PositionedString};
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Statement<'s> {
+        Call(&'s str),
+        Error,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Error {
+        ExpectedSemicolon { row: usize, column: usize },
+    }
+
+    fn statement(input: PositionedString) -> Result<Statement, PositionedString, Error> {
+        take_while1(input, |c| c.is_alphabetic()).and(|name, rest| {
+            tag(rest, "();")
+                .map(|_call| Statement::Call(name.content))
+                .cut(|| Error::ExpectedSemicolon {
+                    row: rest.position.row,
+                    column: rest.position.column,
+                })
+        })
+    }
+
+    #[test]
+    fn test_recovering() {
+        let mut errors = Vec::new();
+
+        assert_eq!(
+            recover(
+                &mut errors,
+                "a(\nb();",
+                |input| tag(input, "a();").cut(|| "expected a call"),
+                |c| *c == '\n',
+                |_error| "error"
+            ),
+            Ok("error", "b();")
+        );
+
+        assert_eq!(errors, vec!["expected a call"]);
+
+        assert_eq!(
+            recover(
+                &mut errors,
+                "x",
+                |input| tag(input, "a();"),
+                |c| *c == '\n',
+                |_error| "error"
+            ),
+            Err
+        );
+
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_collecting_recovering() {
+        let input = PositionedString::from("foo();bar(;baz();qux;quux();!");
+
+        let recovered = collect_recovering(
+            Vec::new(),
+            input,
+            statement,
+            |c| *c == ';',
+            |_error| Statement::Error,
+        );
+
+        assert_eq!(
+            recovered.output,
+            vec![
+                Statement::Call("foo"),
+                Statement::Error,
+                Statement::Call("baz"),
+                Statement::Error,
+                Statement::Call("quux"),
+            ]
+        );
+
+        assert_eq!(recovered.rest.content, "!");
+
+        assert_eq!(
+            recovered.errors,
+            vec![
+                Error::ExpectedSemicolon { row: 1, column: 10 },
+                Error::ExpectedSemicolon { row: 1, column: 21 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recovering_at_the_end() {
+        let recovered = collect_recovering(
+            Vec::new(),
+            PositionedString::from("foo"),
+            statement,
+            |c| *c == ';',
+            |_error| Statement::Error,
+        );
+
+        assert_eq!(recovered.output, vec![Statement::Error]);
+
+        assert_eq!(recovered.rest.content, "");
+
+        assert_eq!(recovered.errors.len(), 1);
+
+        let recovered = collect_recovering(
+            Vec::new(),
+            "a;",
+            |input| tag(input, "a").cut(|| "expected `a`"),
+            |c| *c == ';',
+            |_error| "ERR",
+        );
+
+        assert_eq!(recovered.output, vec!["a", "ERR", "ERR"]);
+
+        assert_eq!(recovered.errors.len(), 2);
+
+        assert_eq!(recovered.rest, "");
+    }
+}
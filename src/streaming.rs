@@ -0,0 +1,459 @@
+use crate::{Input, SliceInput};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Needed {
+    /// At least this many more parts are needed
+    Size(usize),
+    /// It is not known how much more input is needed
+    Unknown,
+}
+
+/// `parco::Result` for input that arrives in chunks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Result<T, I, F> {
+    /// Parsing completed successfully
+    Ok(T, I),
+    /// Recoverable error meaning "input cannot be parsed with the current parser"
+    Err,
+    /// Unrecoverable error meaning "input cannot be parsed with any parser"
+    Fatal(F),
+    /// The input ended too early, parse again when there is more of it
+    Incomplete(Needed),
+}
+
+use self::Result::{Err, Fatal, Incomplete, Ok};
+
+impl<T, I, F> Result<T, I, F> {
+    pub fn and<OT, OI>(self, f: impl FnOnce(T, I) -> Result<OT, OI, F>) -> Result<OT, OI, F> {
+        match self {
+            Ok(result, rest) => f(result, rest),
+            Err => Err,
+            Fatal(e) => Fatal(e),
+            Incomplete(needed) => Incomplete(needed),
+        }
+    }
+
+    /// `Incomplete` is not an alternative to try the next parser after: the current parser may
+    /// still succeed when there is more input
+    pub fn or(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Err => f(),
+            result => result,
+        }
+    }
+
+    pub fn map<O>(self, f: impl FnOnce(T) -> O) -> Result<O, I, F> {
+        match self {
+            Ok(result, rest) => Ok(f(result), rest),
+            Err => Err,
+            Fatal(e) => Fatal(e),
+            Incomplete(needed) => Incomplete(needed),
+        }
+    }
+
+    /// Turns `Err` into `Fatal`, see `parco::Result::cut()`
+    pub fn cut(self, f: impl FnOnce() -> F) -> Self {
+        match self {
+            Err => Fatal(f()),
+            result => result,
+        }
+    }
+}
+
+/// A chunk of input that may be followed by more input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Partial<I> {
+    pub content: I,
+    /// Whether the end of `content` is the end of the whole input
+    pub complete: bool,
+}
+
+impl<I> Partial<I> {
+    fn with(self, content: I) -> Self {
+        Self {
+            content,
+            complete: self.complete,
+        }
+    }
+
+    fn end<T, F>(&self, needed: Needed) -> Result<T, Self, F> {
+        if self.complete {
+            Err
+        } else {
+            Incomplete(needed)
+        }
+    }
+}
+
+pub fn one_part<I: Input, F>(input: Partial<I>) -> Result<I::Part, Partial<I>, F> {
+    match input.content.take_one_part() {
+        Some((part, rest)) => Ok(part, input.with(rest)),
+        None => input.end(Needed::Size(1)),
+    }
+}
+
+pub fn one_matching_part<I: Input, F>(
+    input: Partial<I>,
+    f: impl FnOnce(&I::Part) -> bool,
+) -> Result<I::Part, Partial<I>, F> {
+    one_part(input).and(|part, rest| if f(&part) { Ok(part, rest) } else { Err })
+}
+
+/// Like `parco::take_while()`, but reaching the end of an incomplete input is `Incomplete`, since
+/// more matching parts may follow
+pub fn take_while<I: SliceInput + Clone, F>(
+    input: Partial<I>,
+    mut f: impl FnMut(&I::Part) -> bool,
+) -> Result<I::Slice, Partial<I>, F> {
+    let mut rest = input.content.clone();
+    loop {
+        match rest.take_one_part() {
+            Some((part, next)) if f(&part) => rest = next,
+            Some(_part) => break,
+            None if input.complete => break,
+            None => return Incomplete(Needed::Unknown),
+        }
+    }
+    Ok(input.content.slice_to(&rest), input.with(rest))
+}
+
+pub fn tag<'s, F>(input: Partial<&'s str>, tag: &str) -> Result<&'s str, Partial<&'s str>, F> {
+    if let Some(rest) = input.content.strip_prefix(tag) {
+        Ok(&input.content[..tag.len()], input.with(rest))
+    } else if tag.starts_with(input.content) {
+        input.end(Needed::Size(
+            tag.chars().count() - input.content.chars().count(),
+        ))
+    } else {
+        Err
+    }
+}
+
+pub fn take<F>(input: Partial<&[u8]>, count: usize) -> Result<&[u8], Partial<&[u8]>, F> {
+    if input.content.len() < count {
+        input.end(Needed::Size(count - input.content.len()))
+    } else {
+        let (taken, rest) = input.content.split_at(count);
+        Ok(taken, input.with(rest))
+    }
+}
+
+pub fn exact<'a, F>(
+    input: Partial<&'a [u8]>,
+    expected: &[u8],
+) -> Result<&'a [u8], Partial<&'a [u8]>, F> {
+    if input.content.starts_with(expected) {
+        take(input, expected.len())
+    } else if expected.starts_with(input.content) {
+        input.end(Needed::Size(expected.len() - input.content.len()))
+    } else {
+        Err
+    }
+}
+
+/// Like `parco::fold_repeating()`, but `Incomplete` from `parser` makes the whole repetition
+/// `Incomplete`, since the item may be parsed when there is more input. The combinators of the
+/// rest of the crate cannot be used on `Partial` inputs, since they would take the end of a chunk
+/// for the end of the input
+pub fn fold_repeating<T, A, I: Input, F>(
+    init: A,
+    input: Partial<I>,
+    mut parser: impl FnMut(&Partial<I>) -> Result<T, Partial<I>, F>,
+    mut f: impl FnMut(A, T) -> A,
+) -> Result<A, Partial<I>, F> {
+    let mut accumulator = init;
+    let mut rest = input;
+    loop {
+        match parser(&rest) {
+            Ok(item, next) => {
+                // The parser would output the same thing forever, so it is treated like `Err`
+                if let (Some(before), Some(after)) =
+                    (rest.content.remaining(), next.content.remaining())
+                {
+                    if after >= before {
+                        break;
+                    }
+                }
+                accumulator = f(accumulator, item);
+                rest = next;
+            }
+            Err => break,
+            Fatal(e) => return Fatal(e),
+            Incomplete(needed) => return Incomplete(needed),
+        }
+    }
+    Ok(accumulator, rest)
+}
+
+/// Like `parco::collect_repeating()`, but passes `Incomplete` through, see `fold_repeating()`
+pub fn collect_repeating<T, I: Input, F, C: Extend<T>>(
+    collection: C,
+    input: Partial<I>,
+    parser: impl FnMut(&Partial<I>) -> Result<T, Partial<I>, F>,
+) -> Result<C, Partial<I>, F> {
+    fold_repeating(collection, input, parser, |mut collection, item| {
+        collection.extend(Some(item));
+        collection
+    })
+}
+
+/// What happened when a parser was run on the data of a `Buffer`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step<T, F> {
+    /// The parser succeeded, the data it consumed is removed from the buffer
+    Parsed(T),
+    /// Feed more data and run the parser again
+    Pending(Needed),
+    Err,
+    Fatal(F),
+}
+
+/// Collects chunks of data and runs parsers on them, resuming when more data arrives
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Buffer {
+    data: Vec<u8>,
+    consumed: usize,
+    complete: bool,
+}
+
+impl Buffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.data.drain(..self.consumed);
+        self.consumed = 0;
+        self.data.extend_from_slice(chunk);
+    }
+
+    /// Tells the buffer that no more data will arrive, so parsers stop returning `Incomplete`
+    pub fn finish(&mut self) {
+        self.complete = true;
+    }
+
+    /// The data that was not consumed yet
+    pub fn unconsumed(&self) -> &[u8] {
+        &self.data[self.consumed..]
+    }
+
+    pub fn parse_bytes<'b, T, F>(
+        &'b mut self,
+        parser: impl FnOnce(Partial<&'b [u8]>) -> Result<T, Partial<&'b [u8]>, F>,
+    ) -> Step<T, F> {
+        let content = &self.data[self.consumed..];
+        let input = Partial {
+            content,
+            complete: self.complete,
+        };
+        match parser(input) {
+            Ok(result, rest) => {
+                self.consumed += content.len() - rest.content.len();
+                Step::Parsed(result)
+            }
+            Err => Step::Err,
+            Fatal(e) => Step::Fatal(e),
+            Incomplete(needed) => Step::Pending(needed),
+        }
+    }
+
+    /// Runs `parser` on the longest valid UTF-8 prefix of the data. A character that is split
+    /// between chunks is left for the next run; the input is complete if the data is invalid
+    /// UTF-8 after the prefix, since more data will not make the prefix any longer
+    pub fn parse_str<'b, T, F>(
+        &'b mut self,
+        parser: impl FnOnce(Partial<&'b str>) -> Result<T, Partial<&'b str>, F>,
+    ) -> Step<T, F> {
+        let data = &self.data[self.consumed..];
+        let (content, complete) = match core::str::from_utf8(data) {
+            core::result::Result::Ok(content) => (content, self.complete),
+            core::result::Result::Err(error) => (
+                core::str::from_utf8(&data[..error.valid_up_to()]).unwrap(),
+                self.complete || error.error_len().is_some(),
+            ),
+        };
+        match parser(Partial { content, complete }) {
+            Ok(result, rest) => {
+                self.consumed += content.len() - rest.content.len();
+                Step::Parsed(result)
+            }
+            Err => Step::Err,
+            Fatal(e) => Step::Fatal(e),
+            Incomplete(needed) => Step::Pending(needed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial<I>(content: I) -> Partial<I> {
+        Partial {
+            content,
+            complete: false,
+        }
+    }
+
+    fn message(input: Partial<&[u8]>) -> Result<&[u8], Partial<&[u8]>, ()> {
+        one_part(input).and(|length, rest| take(rest, length as usize))
+    }
+
+    #[test]
+    fn test_incomplete_input() {
+        assert_eq!(message(partial(&b""[..])), Incomplete(Needed::Size(1)));
+
+        assert_eq!(
+            message(partial(&b"\x03ab"[..])),
+            Incomplete(Needed::Size(1))
+        );
+
+        assert_eq!(
+            message(partial(&b"\x02abc"[..])),
+            Ok(&b"ab"[..], partial(&b"c"[..]))
+        );
+
+        assert_eq!(
+            message(Partial {
+                content: b"\x03ab",
+                complete: true
+            }),
+            Err
+        );
+
+        assert_eq!(
+            exact::<()>(partial(&b"GE"[..]), b"GET"),
+            Incomplete(Needed::Size(1))
+        );
+
+        assert_eq!(exact::<()>(partial(&b"GO"[..]), b"GET"), Err);
+
+        assert_eq!(tag::<()>(partial("le"), "let"), Incomplete(Needed::Size(1)));
+
+        assert_eq!(
+            take_while::<_, ()>(partial("123"), |c| c.is_numeric()),
+            Incomplete(Needed::Unknown)
+        );
+
+        assert_eq!(
+            take_while::<_, ()>(partial("123;"), |c| c.is_numeric()),
+            Ok("123", partial(";"))
+        );
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let partial = Partial {
+            content: "le",
+            complete: false,
+        };
+
+        assert_eq!(
+            tag::<()>(partial, "let").or(|| tag(partial, "l")),
+            Incomplete(Needed::Size(1))
+        );
+
+        assert_eq!(
+            tag::<()>(partial, "var").or(|| tag(partial, "l")),
+            Ok(
+                "l",
+                Partial {
+                    content: "e",
+                    complete: false
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_repeating() {
+        let messages =
+            |input| collect_repeating(Vec::new(), input, |rest: &Partial<&[u8]>| message(*rest));
+
+        assert_eq!(
+            messages(partial(&b"\x01a\x02b"[..])),
+            Incomplete(Needed::Size(1))
+        );
+
+        assert_eq!(
+            messages(Partial {
+                content: b"\x01a\x02bc",
+                complete: true
+            }),
+            Ok(
+                vec![&b"a"[..], &b"bc"[..]],
+                Partial {
+                    content: &b""[..],
+                    complete: true
+                }
+            )
+        );
+
+        assert_eq!(
+            fold_repeating(
+                0,
+                partial("aab"),
+                |rest| tag::<()>(*rest, "a"),
+                |count, _tag| count + 1
+            ),
+            Ok(2, partial("b"))
+        );
+    }
+
+    #[test]
+    fn test_feeding_bytes() {
+        let mut buffer = Buffer::new();
+
+        assert_eq!(buffer.parse_bytes(message), Step::Pending(Needed::Size(1)));
+
+        buffer.feed(b"\x03a");
+
+        assert_eq!(buffer.parse_bytes(message), Step::Pending(Needed::Size(2)));
+
+        buffer.feed(b"bc\x01");
+
+        assert_eq!(buffer.parse_bytes(message), Step::Parsed(&b"abc"[..]));
+
+        assert_eq!(buffer.parse_bytes(message), Step::Pending(Needed::Size(1)));
+
+        buffer.feed(b"d");
+
+        assert_eq!(buffer.parse_bytes(message), Step::Parsed(&b"d"[..]));
+
+        buffer.feed(b"\x05");
+        buffer.finish();
+
+        assert_eq!(buffer.parse_bytes(message), Step::Err);
+
+        assert_eq!(buffer.unconsumed(), b"\x05");
+    }
+
+    #[test]
+    fn test_feeding_text() {
+        fn word(input: Partial<&str>) -> Result<String, Partial<&str>, ()> {
+            take_while(input, |c: &char| c.is_alphabetic())
+                .and(|word, rest| tag(rest, " ").map(|_space| word.to_owned()))
+        }
+
+        let mut buffer = Buffer::new();
+        let text = "привет мир ".as_bytes();
+
+        buffer.feed(&text[..5]);
+
+        assert_eq!(buffer.parse_str(word), Step::Pending(Needed::Unknown));
+
+        buffer.feed(&text[5..14]);
+
+        assert_eq!(buffer.parse_str(word), Step::Parsed("привет".into()));
+
+        assert_eq!(buffer.parse_str(word), Step::Pending(Needed::Unknown));
+
+        buffer.feed(&text[14..]);
+
+        assert_eq!(buffer.parse_str(word), Step::Parsed("мир".into()));
+
+        buffer.feed(b"ab\xff");
+
+        assert_eq!(buffer.parse_str(word), Step::Err);
+    }
+}